@@ -0,0 +1,81 @@
+use ab_glyph::{Font, FontArc};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+#[allow(dead_code, unused_imports)]
+#[path = "../src/gui/cache.rs"]
+mod cache;
+
+use cache::LruCache;
+
+const FONT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/resources/josenfin_sans_regular.ttf"
+);
+const SIZE: u16 = 24;
+
+type FontKey = (&'static str, u16);
+
+fn frame_lines(frame: usize) -> Vec<String> {
+    vec![
+        "level: 4".to_string(),
+        "lines: 37".to_string(),
+        "points: 12800".to_string(),
+        "grade: S1".to_string(),
+        format!("fps: {} | ticks/s: 60 (60)", 60 + frame % 3),
+        "volume: 100% | music: 80% | sfx: 100%".to_string(),
+    ]
+}
+
+// what every text draw did before the cache: read and parse the font file
+fn load_font(path: &str) -> FontArc {
+    let data = std::fs::read(path).unwrap();
+    FontArc::try_from_vec(data).unwrap()
+}
+
+fn glyphs(font: &FontArc, line: &str) -> usize {
+    line.chars()
+        .map(|char| font.glyph_id(char).0 as usize)
+        .sum()
+}
+
+fn reloading(frame: usize) -> usize {
+    frame_lines(frame)
+        .iter()
+        .map(|line| glyphs(&load_font(FONT), line))
+        .sum()
+}
+
+fn cached(fonts: &mut LruCache<FontKey, FontArc>, frame: usize) -> usize {
+    frame_lines(frame)
+        .iter()
+        .map(|line| {
+            let font = fonts
+                .get_or_try_insert(("josenfin_sans_regular.ttf", SIZE), || {
+                    Ok::<_, ()>(load_font(FONT))
+                })
+                .unwrap();
+            glyphs(font, line)
+        })
+        .sum()
+}
+
+fn fonts_per_frame(c: &mut Criterion) {
+    let mut frame = 0;
+    c.bench_function("fonts loaded from file per frame", |b| {
+        b.iter(|| {
+            frame += 1;
+            black_box(reloading(frame))
+        })
+    });
+
+    let mut fonts = LruCache::new(8);
+    c.bench_function("fonts from lru cache per frame", |b| {
+        b.iter(|| {
+            frame += 1;
+            black_box(cached(&mut fonts, frame))
+        })
+    });
+}
+
+criterion_group!(benches, fonts_per_frame);
+criterion_main!(benches);
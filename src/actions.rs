@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Action {
     Left,
     Right,
@@ -17,6 +18,10 @@ impl ActionsHeld {
     pub fn new() -> Self {
         Self(HashMap::new())
     }
+    pub fn reset(&mut self) {
+        self.0.clear();
+    }
+
     pub fn just_pressed(&self, ticks: usize, control: &Action) -> bool {
         self.held_for(ticks, control, |held_for| held_for == 0)
     }
@@ -28,7 +33,7 @@ impl ActionsHeld {
         functor: F,
     ) -> bool {
         self.get(control)
-            .map(|&held_since| ticks - held_since)
+            .and_then(|&held_since| ticks.checked_sub(held_since))
             .is_some_and(functor)
     }
 }
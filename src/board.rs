@@ -28,6 +28,12 @@ impl Board {
         Board(board)
     }
 
+    pub fn stack_height(&self) -> usize {
+        self.iter()
+            .position(|row| row.iter().any(Option::is_some))
+            .map_or(0, |top| Self::HEIGHT - top)
+    }
+
     pub fn lowest_y(
         &self,
         CurrentTetromino {
@@ -84,24 +90,24 @@ impl Board {
         self.pattern_and_position_colliding(&tetromino.pattern(direction), *x, *y)
     }
 
-    pub fn lines_cleared(&mut self) -> usize {
-        let line_clears: Vec<_> = self
-            .iter()
+    pub fn full_rows(&self) -> Vec<usize> {
+        self.iter()
             .enumerate()
             .filter_map(|(i, row)| if !row.contains(&None) { Some(i) } else { None })
-            .collect();
+            .collect()
+    }
 
+    pub fn clear_rows(&mut self, rows: &[usize]) {
         let mut lines_cleared = 0;
         for i in (0..self.len()).rev() {
             let blank_line = std::array::from_fn(|_| None);
             let line = std::mem::replace(&mut self[i], blank_line);
             self[i + lines_cleared] = line;
 
-            if line_clears.contains(&i) {
+            if rows.contains(&i) {
                 lines_cleared += 1;
             }
         }
-        lines_cleared
     }
 }
 
@@ -217,7 +223,10 @@ mod test {
         ",
         );
 
-        assert_eq!(board.lines_cleared(), 10);
+        let rows = board.full_rows();
+        assert_eq!(rows.len(), 10);
+
+        board.clear_rows(&rows);
 
         assert_eq!(board, after);
     }
@@ -0,0 +1,151 @@
+use std::path::PathBuf;
+
+use crate::mode::Mode;
+
+pub const HELP: &str = "\
+reimtris2
+
+usage: reimtris2 [options]
+
+options:
+    -c, --config <path>     use the config file at <path>
+    -m, --mode <mode>       game mode: reimtris, guideline, classic, tgm
+    -l, --level <level>     starting level
+    -s, --seed <seed>       seed for the piece randomizer
+        --replay <path>     play back the replay at <path>
+        --record <path>     record the game to a replay at <path>
+        --size <w>x<h>      window or --export size, e.g. 1280x720
+        --fullscreen        start in fullscreen
+        --tui               play in the terminal instead of a window
+        --headless          play back --replay without a window and print the result
+        --export <dir>      render --replay into numbered PNG frames in <dir>
+        --fps <fps>         frame rate for --export, defaults to 30
+    -h, --help              print this help
+";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Cli {
+    pub help: bool,
+    pub config: Option<PathBuf>,
+    pub mode: Option<Mode>,
+    pub level: Option<usize>,
+    pub seed: Option<u64>,
+    pub replay: Option<PathBuf>,
+    pub record: Option<PathBuf>,
+    pub size: Option<(u32, u32)>,
+    pub fullscreen: bool,
+    pub tui: bool,
+    pub headless: bool,
+    pub export: Option<PathBuf>,
+    pub fps: Option<u32>,
+}
+
+impl Cli {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Cli, String> {
+        let mut cli = Cli::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("missing value for '{name}', see --help"))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => cli.help = true,
+                "-c" | "--config" => cli.config = Some(value(&arg)?.into()),
+                "-m" | "--mode" => cli.mode = Some(value(&arg)?.parse()?),
+                "-l" | "--level" => cli.level = Some(parse_number(&arg, &value(&arg)?)?),
+                "-s" | "--seed" => cli.seed = Some(parse_number(&arg, &value(&arg)?)?),
+                "--replay" => cli.replay = Some(value(&arg)?.into()),
+                "--record" => cli.record = Some(value(&arg)?.into()),
+                "--size" => cli.size = Some(parse_size(&value(&arg)?)?),
+                "--fullscreen" => cli.fullscreen = true,
+                "--tui" => cli.tui = true,
+                "--headless" => cli.headless = true,
+                "--export" => cli.export = Some(value(&arg)?.into()),
+                "--fps" => cli.fps = Some(parse_number(&arg, &value(&arg)?)?),
+                _ => return Err(format!("unknown argument '{arg}', see --help")),
+            }
+        }
+
+        if cli.headless && cli.replay.is_none() {
+            return Err("--headless needs a replay to play back, see --help".to_string());
+        }
+        if cli.export.is_some() && cli.replay.is_none() {
+            return Err("--export needs a replay to render, see --help".to_string());
+        }
+        if cli.tui && (cli.size.is_some() || cli.fullscreen) {
+            return Err("--size and --fullscreen cannot be used with --tui".to_string());
+        }
+        if cli.replay.is_some() && cli.record.is_some() {
+            return Err("--replay and --record cannot be used together".to_string());
+        }
+        if cli.replay.is_some() && (cli.mode.is_some() || cli.level.is_some() || cli.seed.is_some())
+        {
+            return Err(
+                "--mode, --level and --seed cannot be used with --replay, the replay sets them"
+                    .to_string(),
+            );
+        }
+
+        Ok(cli)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}' for '{name}', expected a number"))
+}
+
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    let invalid = || format!("invalid window size '{value}', expected <width>x<height>");
+    let (width, height) = value.split_once('x').ok_or_else(invalid)?;
+    let width = width.parse().map_err(|_| invalid())?;
+    let height = height.parse().map_err(|_| invalid())?;
+    Ok((width, height))
+}
+
+#[cfg(test)]
+mod test {
+    use super::Cli;
+    use crate::mode::Mode;
+
+    fn parse(args: &str) -> Result<Cli, String> {
+        Cli::parse(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parses_options() {
+        let cli = parse("--mode classic -l 18 --seed 42 --size 1280x720 --fullscreen").unwrap();
+        assert_eq!(cli.mode, Some(Mode::Classic));
+        assert_eq!(cli.level, Some(18));
+        assert_eq!(cli.seed, Some(42));
+        assert_eq!(cli.size, Some((1280, 720)));
+        assert!(cli.fullscreen);
+    }
+
+    #[test]
+    fn reports_bad_arguments() {
+        assert_eq!(
+            parse("--level high").unwrap_err(),
+            "invalid value 'high' for '--level', expected a number"
+        );
+        assert_eq!(
+            parse("--seed").unwrap_err(),
+            "missing value for '--seed', see --help"
+        );
+        assert!(parse("--headless").is_err());
+        assert!(parse("--export frames").is_err());
+        assert!(parse("--mode tetris").unwrap_err().contains("unknown mode"));
+        for args in [
+            "--replay r --mode classic",
+            "--replay r -l 5",
+            "--seed 1 --replay r",
+        ] {
+            assert!(parse(args)
+                .unwrap_err()
+                .contains("cannot be used with --replay"));
+        }
+    }
+}
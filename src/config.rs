@@ -1,19 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+use crate::actions::Action;
+use crate::mode::{Mode, Settings};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 #[serde(tag = "key")]
 pub enum Key {
-    Zero,
-    One,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
     A,
     B,
     C,
@@ -40,81 +34,829 @@ pub enum Key {
     X,
     Y,
     Z,
-    Up,
-    Down,
-    Left,
-    Right,
+    One,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Zero,
     Enter,
+    Escape,
     Backspace,
+    Tab,
     Space,
+    Minus,
+    Equals,
+    LeftBracket,
+    RightBracket,
+    Backslash,
+    NonUsHash,
+    Semicolon,
+    Apostrophe,
+    Grave,
+    Comma,
+    Period,
+    Slash,
+    CapsLock,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    PrintScreen,
+    ScrollLock,
+    Pause,
+    Insert,
+    Home,
+    PageUp,
+    Delete,
+    End,
+    PageDown,
+    Right,
+    Left,
+    Down,
+    Up,
+    NumLock,
+    NumpadDivide,
+    NumpadMultiply,
+    NumpadMinus,
+    NumpadPlus,
+    NumpadEnter,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    Numpad0,
+    NumpadPeriod,
+    NonUsBackslash,
+    Application,
+    Power,
+    NumpadEquals,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    Execute,
+    Help,
+    Menu,
+    Select,
+    Stop,
+    Again,
+    Undo,
+    Cut,
+    Copy,
+    Paste,
+    Find,
+    Mute,
+    VolumeUp,
+    VolumeDown,
+    NumpadComma,
+    NumpadEqualsAS400,
+    International1,
+    International2,
+    International3,
+    International4,
+    International5,
+    International6,
+    International7,
+    International8,
+    International9,
+    Lang1,
+    Lang2,
+    Lang3,
+    Lang4,
+    Lang5,
+    Lang6,
+    Lang7,
+    Lang8,
+    Lang9,
+    AltErase,
+    SysReq,
+    Cancel,
+    Clear,
+    Prior,
+    Return2,
+    Separator,
+    Out,
+    Oper,
+    ClearAgain,
+    CrSel,
+    ExSel,
+    Numpad00,
+    Numpad000,
+    ThousandsSeparator,
+    DecimalSeparator,
+    CurrencyUnit,
+    CurrencySubUnit,
+    NumpadLeftParen,
+    NumpadRightParen,
+    NumpadLeftBrace,
+    NumpadRightBrace,
+    NumpadTab,
+    NumpadBackspace,
+    NumpadA,
+    NumpadB,
+    NumpadC,
+    NumpadD,
+    NumpadE,
+    NumpadF,
+    NumpadXor,
+    NumpadPower,
+    NumpadPercent,
+    NumpadLess,
+    NumpadGreater,
+    NumpadAmpersand,
+    NumpadDblAmpersand,
+    NumpadVerticalBar,
+    NumpadDblVerticalBar,
+    NumpadColon,
+    NumpadHash,
+    NumpadSpace,
+    NumpadAt,
+    NumpadExclam,
+    NumpadMemStore,
+    NumpadMemRecall,
+    NumpadMemClear,
+    NumpadMemAdd,
+    NumpadMemSubtract,
+    NumpadMemMultiply,
+    NumpadMemDivide,
+    NumpadPlusMinus,
+    NumpadClear,
+    NumpadClearEntry,
+    NumpadBinary,
+    NumpadOctal,
+    NumpadDecimal,
+    NumpadHexadecimal,
+    LeftCtrl,
+    LeftShift,
+    LeftAlt,
+    LeftGui,
+    RightCtrl,
+    RightShift,
+    RightAlt,
+    RightGui,
+    Mode,
+    AudioNext,
+    AudioPrev,
+    AudioStop,
+    AudioPlay,
+    AudioMute,
+    MediaSelect,
+    Www,
+    Mail,
+    Calculator,
+    Computer,
+    AcSearch,
+    AcHome,
+    AcBack,
+    AcForward,
+    AcStop,
+    AcRefresh,
+    AcBookmarks,
+    BrightnessDown,
+    BrightnessUp,
+    DisplaySwitch,
+    KbdIllumToggle,
+    KbdIllumDown,
+    KbdIllumUp,
+    Eject,
+    Sleep,
+    App1,
+    App2,
 }
 
 impl Key {
-    pub fn from_sdl2_keycode(keycode: sdl2::keyboard::Keycode) -> Option<Key> {
-        use sdl2::keyboard::Keycode;
-        let v = match keycode {
-            Keycode::Num0 | Keycode::Kp0 => Key::Zero,
-            Keycode::Num1 | Keycode::Kp1 => Key::One,
-            Keycode::Num2 | Keycode::Kp2 => Key::Two,
-            Keycode::Num3 | Keycode::Kp3 => Key::Three,
-            Keycode::Num4 | Keycode::Kp4 => Key::Four,
-            Keycode::Num5 | Keycode::Kp5 => Key::Five,
-            Keycode::Num6 | Keycode::Kp6 => Key::Six,
-            Keycode::Num7 | Keycode::Kp7 => Key::Seven,
-            Keycode::Num8 | Keycode::Kp8 => Key::Eight,
-            Keycode::Num9 | Keycode::Kp9 => Key::Nine,
-            Keycode::A => Key::A,
-            Keycode::B => Key::B,
-            Keycode::C => Key::C,
-            Keycode::D => Key::D,
-            Keycode::E => Key::E,
-            Keycode::F => Key::F,
-            Keycode::G => Key::G,
-            Keycode::H => Key::H,
-            Keycode::I => Key::I,
-            Keycode::J => Key::J,
-            Keycode::K => Key::K,
-            Keycode::L => Key::L,
-            Keycode::M => Key::M,
-            Keycode::N => Key::N,
-            Keycode::O => Key::O,
-            Keycode::P => Key::P,
-            Keycode::Q => Key::Q,
-            Keycode::R => Key::R,
-            Keycode::S => Key::S,
-            Keycode::T => Key::T,
-            Keycode::U => Key::U,
-            Keycode::V => Key::V,
-            Keycode::W => Key::W,
-            Keycode::X => Key::X,
-            Keycode::Y => Key::Y,
-            Keycode::Z => Key::Z,
-            Keycode::Up => Key::Up,
-            Keycode::Down => Key::Down,
-            Keycode::Left => Key::Left,
-            Keycode::Right => Key::Right,
-            Keycode::Return => Key::Enter,
-            Keycode::Backspace => Key::Backspace,
-            Keycode::Space => Key::Space,
+    pub fn from_sdl2_scancode(scancode: sdl2::keyboard::Scancode) -> Option<Key> {
+        use sdl2::keyboard::Scancode;
+        let v = match scancode {
+            Scancode::A => Key::A,
+            Scancode::B => Key::B,
+            Scancode::C => Key::C,
+            Scancode::D => Key::D,
+            Scancode::E => Key::E,
+            Scancode::F => Key::F,
+            Scancode::G => Key::G,
+            Scancode::H => Key::H,
+            Scancode::I => Key::I,
+            Scancode::J => Key::J,
+            Scancode::K => Key::K,
+            Scancode::L => Key::L,
+            Scancode::M => Key::M,
+            Scancode::N => Key::N,
+            Scancode::O => Key::O,
+            Scancode::P => Key::P,
+            Scancode::Q => Key::Q,
+            Scancode::R => Key::R,
+            Scancode::S => Key::S,
+            Scancode::T => Key::T,
+            Scancode::U => Key::U,
+            Scancode::V => Key::V,
+            Scancode::W => Key::W,
+            Scancode::X => Key::X,
+            Scancode::Y => Key::Y,
+            Scancode::Z => Key::Z,
+            Scancode::Num1 => Key::One,
+            Scancode::Num2 => Key::Two,
+            Scancode::Num3 => Key::Three,
+            Scancode::Num4 => Key::Four,
+            Scancode::Num5 => Key::Five,
+            Scancode::Num6 => Key::Six,
+            Scancode::Num7 => Key::Seven,
+            Scancode::Num8 => Key::Eight,
+            Scancode::Num9 => Key::Nine,
+            Scancode::Num0 => Key::Zero,
+            Scancode::Return => Key::Enter,
+            Scancode::Escape => Key::Escape,
+            Scancode::Backspace => Key::Backspace,
+            Scancode::Tab => Key::Tab,
+            Scancode::Space => Key::Space,
+            Scancode::Minus => Key::Minus,
+            Scancode::Equals => Key::Equals,
+            Scancode::LeftBracket => Key::LeftBracket,
+            Scancode::RightBracket => Key::RightBracket,
+            Scancode::Backslash => Key::Backslash,
+            Scancode::NonUsHash => Key::NonUsHash,
+            Scancode::Semicolon => Key::Semicolon,
+            Scancode::Apostrophe => Key::Apostrophe,
+            Scancode::Grave => Key::Grave,
+            Scancode::Comma => Key::Comma,
+            Scancode::Period => Key::Period,
+            Scancode::Slash => Key::Slash,
+            Scancode::CapsLock => Key::CapsLock,
+            Scancode::F1 => Key::F1,
+            Scancode::F2 => Key::F2,
+            Scancode::F3 => Key::F3,
+            Scancode::F4 => Key::F4,
+            Scancode::F5 => Key::F5,
+            Scancode::F6 => Key::F6,
+            Scancode::F7 => Key::F7,
+            Scancode::F8 => Key::F8,
+            Scancode::F9 => Key::F9,
+            Scancode::F10 => Key::F10,
+            Scancode::F11 => Key::F11,
+            Scancode::F12 => Key::F12,
+            Scancode::PrintScreen => Key::PrintScreen,
+            Scancode::ScrollLock => Key::ScrollLock,
+            Scancode::Pause => Key::Pause,
+            Scancode::Insert => Key::Insert,
+            Scancode::Home => Key::Home,
+            Scancode::PageUp => Key::PageUp,
+            Scancode::Delete => Key::Delete,
+            Scancode::End => Key::End,
+            Scancode::PageDown => Key::PageDown,
+            Scancode::Right => Key::Right,
+            Scancode::Left => Key::Left,
+            Scancode::Down => Key::Down,
+            Scancode::Up => Key::Up,
+            Scancode::NumLockClear => Key::NumLock,
+            Scancode::KpDivide => Key::NumpadDivide,
+            Scancode::KpMultiply => Key::NumpadMultiply,
+            Scancode::KpMinus => Key::NumpadMinus,
+            Scancode::KpPlus => Key::NumpadPlus,
+            Scancode::KpEnter => Key::NumpadEnter,
+            Scancode::Kp1 => Key::Numpad1,
+            Scancode::Kp2 => Key::Numpad2,
+            Scancode::Kp3 => Key::Numpad3,
+            Scancode::Kp4 => Key::Numpad4,
+            Scancode::Kp5 => Key::Numpad5,
+            Scancode::Kp6 => Key::Numpad6,
+            Scancode::Kp7 => Key::Numpad7,
+            Scancode::Kp8 => Key::Numpad8,
+            Scancode::Kp9 => Key::Numpad9,
+            Scancode::Kp0 => Key::Numpad0,
+            Scancode::KpPeriod => Key::NumpadPeriod,
+            Scancode::NonUsBackslash => Key::NonUsBackslash,
+            Scancode::Application => Key::Application,
+            Scancode::Power => Key::Power,
+            Scancode::KpEquals => Key::NumpadEquals,
+            Scancode::F13 => Key::F13,
+            Scancode::F14 => Key::F14,
+            Scancode::F15 => Key::F15,
+            Scancode::F16 => Key::F16,
+            Scancode::F17 => Key::F17,
+            Scancode::F18 => Key::F18,
+            Scancode::F19 => Key::F19,
+            Scancode::F20 => Key::F20,
+            Scancode::F21 => Key::F21,
+            Scancode::F22 => Key::F22,
+            Scancode::F23 => Key::F23,
+            Scancode::F24 => Key::F24,
+            Scancode::Execute => Key::Execute,
+            Scancode::Help => Key::Help,
+            Scancode::Menu => Key::Menu,
+            Scancode::Select => Key::Select,
+            Scancode::Stop => Key::Stop,
+            Scancode::Again => Key::Again,
+            Scancode::Undo => Key::Undo,
+            Scancode::Cut => Key::Cut,
+            Scancode::Copy => Key::Copy,
+            Scancode::Paste => Key::Paste,
+            Scancode::Find => Key::Find,
+            Scancode::Mute => Key::Mute,
+            Scancode::VolumeUp => Key::VolumeUp,
+            Scancode::VolumeDown => Key::VolumeDown,
+            Scancode::KpComma => Key::NumpadComma,
+            Scancode::KpEqualsAS400 => Key::NumpadEqualsAS400,
+            Scancode::International1 => Key::International1,
+            Scancode::International2 => Key::International2,
+            Scancode::International3 => Key::International3,
+            Scancode::International4 => Key::International4,
+            Scancode::International5 => Key::International5,
+            Scancode::International6 => Key::International6,
+            Scancode::International7 => Key::International7,
+            Scancode::International8 => Key::International8,
+            Scancode::International9 => Key::International9,
+            Scancode::Lang1 => Key::Lang1,
+            Scancode::Lang2 => Key::Lang2,
+            Scancode::Lang3 => Key::Lang3,
+            Scancode::Lang4 => Key::Lang4,
+            Scancode::Lang5 => Key::Lang5,
+            Scancode::Lang6 => Key::Lang6,
+            Scancode::Lang7 => Key::Lang7,
+            Scancode::Lang8 => Key::Lang8,
+            Scancode::Lang9 => Key::Lang9,
+            Scancode::AltErase => Key::AltErase,
+            Scancode::SysReq => Key::SysReq,
+            Scancode::Cancel => Key::Cancel,
+            Scancode::Clear => Key::Clear,
+            Scancode::Prior => Key::Prior,
+            Scancode::Return2 => Key::Return2,
+            Scancode::Separator => Key::Separator,
+            Scancode::Out => Key::Out,
+            Scancode::Oper => Key::Oper,
+            Scancode::ClearAgain => Key::ClearAgain,
+            Scancode::CrSel => Key::CrSel,
+            Scancode::ExSel => Key::ExSel,
+            Scancode::Kp00 => Key::Numpad00,
+            Scancode::Kp000 => Key::Numpad000,
+            Scancode::ThousandsSeparator => Key::ThousandsSeparator,
+            Scancode::DecimalSeparator => Key::DecimalSeparator,
+            Scancode::CurrencyUnit => Key::CurrencyUnit,
+            Scancode::CurrencySubUnit => Key::CurrencySubUnit,
+            Scancode::KpLeftParen => Key::NumpadLeftParen,
+            Scancode::KpRightParen => Key::NumpadRightParen,
+            Scancode::KpLeftBrace => Key::NumpadLeftBrace,
+            Scancode::KpRightBrace => Key::NumpadRightBrace,
+            Scancode::KpTab => Key::NumpadTab,
+            Scancode::KpBackspace => Key::NumpadBackspace,
+            Scancode::KpA => Key::NumpadA,
+            Scancode::KpB => Key::NumpadB,
+            Scancode::KpC => Key::NumpadC,
+            Scancode::KpD => Key::NumpadD,
+            Scancode::KpE => Key::NumpadE,
+            Scancode::KpF => Key::NumpadF,
+            Scancode::KpXor => Key::NumpadXor,
+            Scancode::KpPower => Key::NumpadPower,
+            Scancode::KpPercent => Key::NumpadPercent,
+            Scancode::KpLess => Key::NumpadLess,
+            Scancode::KpGreater => Key::NumpadGreater,
+            Scancode::KpAmpersand => Key::NumpadAmpersand,
+            Scancode::KpDblAmpersand => Key::NumpadDblAmpersand,
+            Scancode::KpVerticalBar => Key::NumpadVerticalBar,
+            Scancode::KpDblVerticalBar => Key::NumpadDblVerticalBar,
+            Scancode::KpColon => Key::NumpadColon,
+            Scancode::KpHash => Key::NumpadHash,
+            Scancode::KpSpace => Key::NumpadSpace,
+            Scancode::KpAt => Key::NumpadAt,
+            Scancode::KpExclam => Key::NumpadExclam,
+            Scancode::KpMemStore => Key::NumpadMemStore,
+            Scancode::KpMemRecall => Key::NumpadMemRecall,
+            Scancode::KpMemClear => Key::NumpadMemClear,
+            Scancode::KpMemAdd => Key::NumpadMemAdd,
+            Scancode::KpMemSubtract => Key::NumpadMemSubtract,
+            Scancode::KpMemMultiply => Key::NumpadMemMultiply,
+            Scancode::KpMemDivide => Key::NumpadMemDivide,
+            Scancode::KpPlusMinus => Key::NumpadPlusMinus,
+            Scancode::KpClear => Key::NumpadClear,
+            Scancode::KpClearEntry => Key::NumpadClearEntry,
+            Scancode::KpBinary => Key::NumpadBinary,
+            Scancode::KpOctal => Key::NumpadOctal,
+            Scancode::KpDecimal => Key::NumpadDecimal,
+            Scancode::KpHexadecimal => Key::NumpadHexadecimal,
+            Scancode::LCtrl => Key::LeftCtrl,
+            Scancode::LShift => Key::LeftShift,
+            Scancode::LAlt => Key::LeftAlt,
+            Scancode::LGui => Key::LeftGui,
+            Scancode::RCtrl => Key::RightCtrl,
+            Scancode::RShift => Key::RightShift,
+            Scancode::RAlt => Key::RightAlt,
+            Scancode::RGui => Key::RightGui,
+            Scancode::Mode => Key::Mode,
+            Scancode::AudioNext => Key::AudioNext,
+            Scancode::AudioPrev => Key::AudioPrev,
+            Scancode::AudioStop => Key::AudioStop,
+            Scancode::AudioPlay => Key::AudioPlay,
+            Scancode::AudioMute => Key::AudioMute,
+            Scancode::MediaSelect => Key::MediaSelect,
+            Scancode::Www => Key::Www,
+            Scancode::Mail => Key::Mail,
+            Scancode::Calculator => Key::Calculator,
+            Scancode::Computer => Key::Computer,
+            Scancode::AcSearch => Key::AcSearch,
+            Scancode::AcHome => Key::AcHome,
+            Scancode::AcBack => Key::AcBack,
+            Scancode::AcForward => Key::AcForward,
+            Scancode::AcStop => Key::AcStop,
+            Scancode::AcRefresh => Key::AcRefresh,
+            Scancode::AcBookmarks => Key::AcBookmarks,
+            Scancode::BrightnessDown => Key::BrightnessDown,
+            Scancode::BrightnessUp => Key::BrightnessUp,
+            Scancode::DisplaySwitch => Key::DisplaySwitch,
+            Scancode::KbdIllumToggle => Key::KbdIllumToggle,
+            Scancode::KbdIllumDown => Key::KbdIllumDown,
+            Scancode::KbdIllumUp => Key::KbdIllumUp,
+            Scancode::Eject => Key::Eject,
+            Scancode::Sleep => Key::Sleep,
+            Scancode::App1 => Key::App1,
+            Scancode::App2 => Key::App2,
+            Scancode::Num => return None,
+        };
+        Some(v)
+    }
+
+    pub fn from_crossterm(code: crossterm::event::KeyCode) -> Option<Key> {
+        use crossterm::event::KeyCode;
+        let v = match code {
+            KeyCode::Char(char) => return Key::from_char(char),
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Esc => Key::Escape,
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Tab | KeyCode::BackTab => Key::Tab,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right => Key::Right,
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Home => Key::Home,
+            KeyCode::End => Key::End,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::PageDown => Key::PageDown,
+            KeyCode::Insert => Key::Insert,
+            KeyCode::Delete => Key::Delete,
+            KeyCode::CapsLock => Key::CapsLock,
+            KeyCode::ScrollLock => Key::ScrollLock,
+            KeyCode::NumLock => Key::NumLock,
+            KeyCode::PrintScreen => Key::PrintScreen,
+            KeyCode::Pause => Key::Pause,
+            KeyCode::Menu => Key::Menu,
+            KeyCode::F(1) => Key::F1,
+            KeyCode::F(2) => Key::F2,
+            KeyCode::F(3) => Key::F3,
+            KeyCode::F(4) => Key::F4,
+            KeyCode::F(5) => Key::F5,
+            KeyCode::F(6) => Key::F6,
+            KeyCode::F(7) => Key::F7,
+            KeyCode::F(8) => Key::F8,
+            KeyCode::F(9) => Key::F9,
+            KeyCode::F(10) => Key::F10,
+            KeyCode::F(11) => Key::F11,
+            KeyCode::F(12) => Key::F12,
+            KeyCode::F(13) => Key::F13,
+            KeyCode::F(14) => Key::F14,
+            KeyCode::F(15) => Key::F15,
+            KeyCode::F(16) => Key::F16,
+            KeyCode::F(17) => Key::F17,
+            KeyCode::F(18) => Key::F18,
+            KeyCode::F(19) => Key::F19,
+            KeyCode::F(20) => Key::F20,
+            KeyCode::F(21) => Key::F21,
+            KeyCode::F(22) => Key::F22,
+            KeyCode::F(23) => Key::F23,
+            KeyCode::F(24) => Key::F24,
+            _ => return None,
+        };
+        Some(v)
+    }
+
+    fn from_char(char: char) -> Option<Key> {
+        let v = match char {
+            'a' | 'A' => Key::A,
+            'b' | 'B' => Key::B,
+            'c' | 'C' => Key::C,
+            'd' | 'D' => Key::D,
+            'e' | 'E' => Key::E,
+            'f' | 'F' => Key::F,
+            'g' | 'G' => Key::G,
+            'h' | 'H' => Key::H,
+            'i' | 'I' => Key::I,
+            'j' | 'J' => Key::J,
+            'k' | 'K' => Key::K,
+            'l' | 'L' => Key::L,
+            'm' | 'M' => Key::M,
+            'n' | 'N' => Key::N,
+            'o' | 'O' => Key::O,
+            'p' | 'P' => Key::P,
+            'q' | 'Q' => Key::Q,
+            'r' | 'R' => Key::R,
+            's' | 'S' => Key::S,
+            't' | 'T' => Key::T,
+            'u' | 'U' => Key::U,
+            'v' | 'V' => Key::V,
+            'w' | 'W' => Key::W,
+            'x' | 'X' => Key::X,
+            'y' | 'Y' => Key::Y,
+            'z' | 'Z' => Key::Z,
+            '0' => Key::Zero,
+            '1' => Key::One,
+            '2' => Key::Two,
+            '3' => Key::Three,
+            '4' => Key::Four,
+            '5' => Key::Five,
+            '6' => Key::Six,
+            '7' => Key::Seven,
+            '8' => Key::Eight,
+            '9' => Key::Nine,
+            ' ' => Key::Space,
+            '-' | '_' => Key::Minus,
+            '=' | '+' => Key::Equals,
+            '[' | '{' => Key::LeftBracket,
+            ']' | '}' => Key::RightBracket,
+            '\\' | '|' => Key::Backslash,
+            ';' | ':' => Key::Semicolon,
+            '\'' | '"' => Key::Apostrophe,
+            '`' | '~' => Key::Grave,
+            ',' | '<' => Key::Comma,
+            '.' | '>' => Key::Period,
+            '/' | '?' => Key::Slash,
             _ => return None,
         };
         Some(v)
     }
+
+    pub fn to_sdl2_scancode(self) -> sdl2::keyboard::Scancode {
+        use sdl2::keyboard::Scancode;
+        match self {
+            Key::A => Scancode::A,
+            Key::B => Scancode::B,
+            Key::C => Scancode::C,
+            Key::D => Scancode::D,
+            Key::E => Scancode::E,
+            Key::F => Scancode::F,
+            Key::G => Scancode::G,
+            Key::H => Scancode::H,
+            Key::I => Scancode::I,
+            Key::J => Scancode::J,
+            Key::K => Scancode::K,
+            Key::L => Scancode::L,
+            Key::M => Scancode::M,
+            Key::N => Scancode::N,
+            Key::O => Scancode::O,
+            Key::P => Scancode::P,
+            Key::Q => Scancode::Q,
+            Key::R => Scancode::R,
+            Key::S => Scancode::S,
+            Key::T => Scancode::T,
+            Key::U => Scancode::U,
+            Key::V => Scancode::V,
+            Key::W => Scancode::W,
+            Key::X => Scancode::X,
+            Key::Y => Scancode::Y,
+            Key::Z => Scancode::Z,
+            Key::One => Scancode::Num1,
+            Key::Two => Scancode::Num2,
+            Key::Three => Scancode::Num3,
+            Key::Four => Scancode::Num4,
+            Key::Five => Scancode::Num5,
+            Key::Six => Scancode::Num6,
+            Key::Seven => Scancode::Num7,
+            Key::Eight => Scancode::Num8,
+            Key::Nine => Scancode::Num9,
+            Key::Zero => Scancode::Num0,
+            Key::Enter => Scancode::Return,
+            Key::Escape => Scancode::Escape,
+            Key::Backspace => Scancode::Backspace,
+            Key::Tab => Scancode::Tab,
+            Key::Space => Scancode::Space,
+            Key::Minus => Scancode::Minus,
+            Key::Equals => Scancode::Equals,
+            Key::LeftBracket => Scancode::LeftBracket,
+            Key::RightBracket => Scancode::RightBracket,
+            Key::Backslash => Scancode::Backslash,
+            Key::NonUsHash => Scancode::NonUsHash,
+            Key::Semicolon => Scancode::Semicolon,
+            Key::Apostrophe => Scancode::Apostrophe,
+            Key::Grave => Scancode::Grave,
+            Key::Comma => Scancode::Comma,
+            Key::Period => Scancode::Period,
+            Key::Slash => Scancode::Slash,
+            Key::CapsLock => Scancode::CapsLock,
+            Key::F1 => Scancode::F1,
+            Key::F2 => Scancode::F2,
+            Key::F3 => Scancode::F3,
+            Key::F4 => Scancode::F4,
+            Key::F5 => Scancode::F5,
+            Key::F6 => Scancode::F6,
+            Key::F7 => Scancode::F7,
+            Key::F8 => Scancode::F8,
+            Key::F9 => Scancode::F9,
+            Key::F10 => Scancode::F10,
+            Key::F11 => Scancode::F11,
+            Key::F12 => Scancode::F12,
+            Key::PrintScreen => Scancode::PrintScreen,
+            Key::ScrollLock => Scancode::ScrollLock,
+            Key::Pause => Scancode::Pause,
+            Key::Insert => Scancode::Insert,
+            Key::Home => Scancode::Home,
+            Key::PageUp => Scancode::PageUp,
+            Key::Delete => Scancode::Delete,
+            Key::End => Scancode::End,
+            Key::PageDown => Scancode::PageDown,
+            Key::Right => Scancode::Right,
+            Key::Left => Scancode::Left,
+            Key::Down => Scancode::Down,
+            Key::Up => Scancode::Up,
+            Key::NumLock => Scancode::NumLockClear,
+            Key::NumpadDivide => Scancode::KpDivide,
+            Key::NumpadMultiply => Scancode::KpMultiply,
+            Key::NumpadMinus => Scancode::KpMinus,
+            Key::NumpadPlus => Scancode::KpPlus,
+            Key::NumpadEnter => Scancode::KpEnter,
+            Key::Numpad1 => Scancode::Kp1,
+            Key::Numpad2 => Scancode::Kp2,
+            Key::Numpad3 => Scancode::Kp3,
+            Key::Numpad4 => Scancode::Kp4,
+            Key::Numpad5 => Scancode::Kp5,
+            Key::Numpad6 => Scancode::Kp6,
+            Key::Numpad7 => Scancode::Kp7,
+            Key::Numpad8 => Scancode::Kp8,
+            Key::Numpad9 => Scancode::Kp9,
+            Key::Numpad0 => Scancode::Kp0,
+            Key::NumpadPeriod => Scancode::KpPeriod,
+            Key::NonUsBackslash => Scancode::NonUsBackslash,
+            Key::Application => Scancode::Application,
+            Key::Power => Scancode::Power,
+            Key::NumpadEquals => Scancode::KpEquals,
+            Key::F13 => Scancode::F13,
+            Key::F14 => Scancode::F14,
+            Key::F15 => Scancode::F15,
+            Key::F16 => Scancode::F16,
+            Key::F17 => Scancode::F17,
+            Key::F18 => Scancode::F18,
+            Key::F19 => Scancode::F19,
+            Key::F20 => Scancode::F20,
+            Key::F21 => Scancode::F21,
+            Key::F22 => Scancode::F22,
+            Key::F23 => Scancode::F23,
+            Key::F24 => Scancode::F24,
+            Key::Execute => Scancode::Execute,
+            Key::Help => Scancode::Help,
+            Key::Menu => Scancode::Menu,
+            Key::Select => Scancode::Select,
+            Key::Stop => Scancode::Stop,
+            Key::Again => Scancode::Again,
+            Key::Undo => Scancode::Undo,
+            Key::Cut => Scancode::Cut,
+            Key::Copy => Scancode::Copy,
+            Key::Paste => Scancode::Paste,
+            Key::Find => Scancode::Find,
+            Key::Mute => Scancode::Mute,
+            Key::VolumeUp => Scancode::VolumeUp,
+            Key::VolumeDown => Scancode::VolumeDown,
+            Key::NumpadComma => Scancode::KpComma,
+            Key::NumpadEqualsAS400 => Scancode::KpEqualsAS400,
+            Key::International1 => Scancode::International1,
+            Key::International2 => Scancode::International2,
+            Key::International3 => Scancode::International3,
+            Key::International4 => Scancode::International4,
+            Key::International5 => Scancode::International5,
+            Key::International6 => Scancode::International6,
+            Key::International7 => Scancode::International7,
+            Key::International8 => Scancode::International8,
+            Key::International9 => Scancode::International9,
+            Key::Lang1 => Scancode::Lang1,
+            Key::Lang2 => Scancode::Lang2,
+            Key::Lang3 => Scancode::Lang3,
+            Key::Lang4 => Scancode::Lang4,
+            Key::Lang5 => Scancode::Lang5,
+            Key::Lang6 => Scancode::Lang6,
+            Key::Lang7 => Scancode::Lang7,
+            Key::Lang8 => Scancode::Lang8,
+            Key::Lang9 => Scancode::Lang9,
+            Key::AltErase => Scancode::AltErase,
+            Key::SysReq => Scancode::SysReq,
+            Key::Cancel => Scancode::Cancel,
+            Key::Clear => Scancode::Clear,
+            Key::Prior => Scancode::Prior,
+            Key::Return2 => Scancode::Return2,
+            Key::Separator => Scancode::Separator,
+            Key::Out => Scancode::Out,
+            Key::Oper => Scancode::Oper,
+            Key::ClearAgain => Scancode::ClearAgain,
+            Key::CrSel => Scancode::CrSel,
+            Key::ExSel => Scancode::ExSel,
+            Key::Numpad00 => Scancode::Kp00,
+            Key::Numpad000 => Scancode::Kp000,
+            Key::ThousandsSeparator => Scancode::ThousandsSeparator,
+            Key::DecimalSeparator => Scancode::DecimalSeparator,
+            Key::CurrencyUnit => Scancode::CurrencyUnit,
+            Key::CurrencySubUnit => Scancode::CurrencySubUnit,
+            Key::NumpadLeftParen => Scancode::KpLeftParen,
+            Key::NumpadRightParen => Scancode::KpRightParen,
+            Key::NumpadLeftBrace => Scancode::KpLeftBrace,
+            Key::NumpadRightBrace => Scancode::KpRightBrace,
+            Key::NumpadTab => Scancode::KpTab,
+            Key::NumpadBackspace => Scancode::KpBackspace,
+            Key::NumpadA => Scancode::KpA,
+            Key::NumpadB => Scancode::KpB,
+            Key::NumpadC => Scancode::KpC,
+            Key::NumpadD => Scancode::KpD,
+            Key::NumpadE => Scancode::KpE,
+            Key::NumpadF => Scancode::KpF,
+            Key::NumpadXor => Scancode::KpXor,
+            Key::NumpadPower => Scancode::KpPower,
+            Key::NumpadPercent => Scancode::KpPercent,
+            Key::NumpadLess => Scancode::KpLess,
+            Key::NumpadGreater => Scancode::KpGreater,
+            Key::NumpadAmpersand => Scancode::KpAmpersand,
+            Key::NumpadDblAmpersand => Scancode::KpDblAmpersand,
+            Key::NumpadVerticalBar => Scancode::KpVerticalBar,
+            Key::NumpadDblVerticalBar => Scancode::KpDblVerticalBar,
+            Key::NumpadColon => Scancode::KpColon,
+            Key::NumpadHash => Scancode::KpHash,
+            Key::NumpadSpace => Scancode::KpSpace,
+            Key::NumpadAt => Scancode::KpAt,
+            Key::NumpadExclam => Scancode::KpExclam,
+            Key::NumpadMemStore => Scancode::KpMemStore,
+            Key::NumpadMemRecall => Scancode::KpMemRecall,
+            Key::NumpadMemClear => Scancode::KpMemClear,
+            Key::NumpadMemAdd => Scancode::KpMemAdd,
+            Key::NumpadMemSubtract => Scancode::KpMemSubtract,
+            Key::NumpadMemMultiply => Scancode::KpMemMultiply,
+            Key::NumpadMemDivide => Scancode::KpMemDivide,
+            Key::NumpadPlusMinus => Scancode::KpPlusMinus,
+            Key::NumpadClear => Scancode::KpClear,
+            Key::NumpadClearEntry => Scancode::KpClearEntry,
+            Key::NumpadBinary => Scancode::KpBinary,
+            Key::NumpadOctal => Scancode::KpOctal,
+            Key::NumpadDecimal => Scancode::KpDecimal,
+            Key::NumpadHexadecimal => Scancode::KpHexadecimal,
+            Key::LeftCtrl => Scancode::LCtrl,
+            Key::LeftShift => Scancode::LShift,
+            Key::LeftAlt => Scancode::LAlt,
+            Key::LeftGui => Scancode::LGui,
+            Key::RightCtrl => Scancode::RCtrl,
+            Key::RightShift => Scancode::RShift,
+            Key::RightAlt => Scancode::RAlt,
+            Key::RightGui => Scancode::RGui,
+            Key::Mode => Scancode::Mode,
+            Key::AudioNext => Scancode::AudioNext,
+            Key::AudioPrev => Scancode::AudioPrev,
+            Key::AudioStop => Scancode::AudioStop,
+            Key::AudioPlay => Scancode::AudioPlay,
+            Key::AudioMute => Scancode::AudioMute,
+            Key::MediaSelect => Scancode::MediaSelect,
+            Key::Www => Scancode::Www,
+            Key::Mail => Scancode::Mail,
+            Key::Calculator => Scancode::Calculator,
+            Key::Computer => Scancode::Computer,
+            Key::AcSearch => Scancode::AcSearch,
+            Key::AcHome => Scancode::AcHome,
+            Key::AcBack => Scancode::AcBack,
+            Key::AcForward => Scancode::AcForward,
+            Key::AcStop => Scancode::AcStop,
+            Key::AcRefresh => Scancode::AcRefresh,
+            Key::AcBookmarks => Scancode::AcBookmarks,
+            Key::BrightnessDown => Scancode::BrightnessDown,
+            Key::BrightnessUp => Scancode::BrightnessUp,
+            Key::DisplaySwitch => Scancode::DisplaySwitch,
+            Key::KbdIllumToggle => Scancode::KbdIllumToggle,
+            Key::KbdIllumDown => Scancode::KbdIllumDown,
+            Key::KbdIllumUp => Scancode::KbdIllumUp,
+            Key::Eject => Scancode::Eject,
+            Key::Sleep => Scancode::Sleep,
+            Key::App1 => Scancode::App1,
+            Key::App2 => Scancode::App2,
+        }
+    }
 }
 
 impl std::fmt::Display for Key {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let val = match self {
-            Key::Zero => "0",
-            Key::One => "1",
-            Key::Two => "2",
-            Key::Three => "3",
-            Key::Four => "4",
-            Key::Five => "5",
-            Key::Six => "6",
-            Key::Seven => "7",
-            Key::Eight => "8",
-            Key::Nine => "9",
             Key::A => "A",
             Key::B => "B",
             Key::C => "C",
@@ -141,73 +883,1048 @@ impl std::fmt::Display for Key {
             Key::X => "X",
             Key::Y => "Y",
             Key::Z => "Z",
-            Key::Up => "Up",
-            Key::Down => "Down",
-            Key::Left => "Left",
-            Key::Right => "Right",
+            Key::One => "1",
+            Key::Two => "2",
+            Key::Three => "3",
+            Key::Four => "4",
+            Key::Five => "5",
+            Key::Six => "6",
+            Key::Seven => "7",
+            Key::Eight => "8",
+            Key::Nine => "9",
+            Key::Zero => "0",
             Key::Enter => "Enter",
+            Key::Escape => "Escape",
             Key::Backspace => "Backspace",
+            Key::Tab => "Tab",
             Key::Space => "Space",
+            Key::Minus => "-",
+            Key::Equals => "=",
+            Key::LeftBracket => "[",
+            Key::RightBracket => "]",
+            Key::Backslash => "\\",
+            Key::NonUsHash => "#",
+            Key::Semicolon => ";",
+            Key::Apostrophe => "'",
+            Key::Grave => "`",
+            Key::Comma => ",",
+            Key::Period => ".",
+            Key::Slash => "/",
+            Key::CapsLock => "Caps Lock",
+            Key::F1 => "F1",
+            Key::F2 => "F2",
+            Key::F3 => "F3",
+            Key::F4 => "F4",
+            Key::F5 => "F5",
+            Key::F6 => "F6",
+            Key::F7 => "F7",
+            Key::F8 => "F8",
+            Key::F9 => "F9",
+            Key::F10 => "F10",
+            Key::F11 => "F11",
+            Key::F12 => "F12",
+            Key::PrintScreen => "Print Screen",
+            Key::ScrollLock => "Scroll Lock",
+            Key::Pause => "Pause",
+            Key::Insert => "Insert",
+            Key::Home => "Home",
+            Key::PageUp => "Page Up",
+            Key::Delete => "Delete",
+            Key::End => "End",
+            Key::PageDown => "Page Down",
+            Key::Right => "Right",
+            Key::Left => "Left",
+            Key::Down => "Down",
+            Key::Up => "Up",
+            Key::NumLock => "Num Lock",
+            Key::NumpadDivide => "Numpad Divide",
+            Key::NumpadMultiply => "Numpad Multiply",
+            Key::NumpadMinus => "Numpad Minus",
+            Key::NumpadPlus => "Numpad Plus",
+            Key::NumpadEnter => "Numpad Enter",
+            Key::Numpad1 => "Numpad 1",
+            Key::Numpad2 => "Numpad 2",
+            Key::Numpad3 => "Numpad 3",
+            Key::Numpad4 => "Numpad 4",
+            Key::Numpad5 => "Numpad 5",
+            Key::Numpad6 => "Numpad 6",
+            Key::Numpad7 => "Numpad 7",
+            Key::Numpad8 => "Numpad 8",
+            Key::Numpad9 => "Numpad 9",
+            Key::Numpad0 => "Numpad 0",
+            Key::NumpadPeriod => "Numpad Period",
+            Key::NonUsBackslash => "<",
+            Key::Application => "Application",
+            Key::Power => "Power",
+            Key::NumpadEquals => "Numpad Equals",
+            Key::F13 => "F13",
+            Key::F14 => "F14",
+            Key::F15 => "F15",
+            Key::F16 => "F16",
+            Key::F17 => "F17",
+            Key::F18 => "F18",
+            Key::F19 => "F19",
+            Key::F20 => "F20",
+            Key::F21 => "F21",
+            Key::F22 => "F22",
+            Key::F23 => "F23",
+            Key::F24 => "F24",
+            Key::Execute => "Execute",
+            Key::Help => "Help",
+            Key::Menu => "Menu",
+            Key::Select => "Select",
+            Key::Stop => "Stop",
+            Key::Again => "Again",
+            Key::Undo => "Undo",
+            Key::Cut => "Cut",
+            Key::Copy => "Copy",
+            Key::Paste => "Paste",
+            Key::Find => "Find",
+            Key::Mute => "Mute",
+            Key::VolumeUp => "Volume Up",
+            Key::VolumeDown => "Volume Down",
+            Key::NumpadComma => "Numpad Comma",
+            Key::NumpadEqualsAS400 => "Numpad Equals AS400",
+            Key::International1 => "International1",
+            Key::International2 => "International2",
+            Key::International3 => "International3",
+            Key::International4 => "International4",
+            Key::International5 => "International5",
+            Key::International6 => "International6",
+            Key::International7 => "International7",
+            Key::International8 => "International8",
+            Key::International9 => "International9",
+            Key::Lang1 => "Lang1",
+            Key::Lang2 => "Lang2",
+            Key::Lang3 => "Lang3",
+            Key::Lang4 => "Lang4",
+            Key::Lang5 => "Lang5",
+            Key::Lang6 => "Lang6",
+            Key::Lang7 => "Lang7",
+            Key::Lang8 => "Lang8",
+            Key::Lang9 => "Lang9",
+            Key::AltErase => "Alt Erase",
+            Key::SysReq => "Sys Req",
+            Key::Cancel => "Cancel",
+            Key::Clear => "Clear",
+            Key::Prior => "Prior",
+            Key::Return2 => "Return2",
+            Key::Separator => "Separator",
+            Key::Out => "Out",
+            Key::Oper => "Oper",
+            Key::ClearAgain => "Clear Again",
+            Key::CrSel => "Cr Sel",
+            Key::ExSel => "Ex Sel",
+            Key::Numpad00 => "Numpad 00",
+            Key::Numpad000 => "Numpad 000",
+            Key::ThousandsSeparator => "Thousands Separator",
+            Key::DecimalSeparator => "Decimal Separator",
+            Key::CurrencyUnit => "Currency Unit",
+            Key::CurrencySubUnit => "Currency Sub Unit",
+            Key::NumpadLeftParen => "Numpad Left Paren",
+            Key::NumpadRightParen => "Numpad Right Paren",
+            Key::NumpadLeftBrace => "Numpad Left Brace",
+            Key::NumpadRightBrace => "Numpad Right Brace",
+            Key::NumpadTab => "Numpad Tab",
+            Key::NumpadBackspace => "Numpad Backspace",
+            Key::NumpadA => "Numpad A",
+            Key::NumpadB => "Numpad B",
+            Key::NumpadC => "Numpad C",
+            Key::NumpadD => "Numpad D",
+            Key::NumpadE => "Numpad E",
+            Key::NumpadF => "Numpad F",
+            Key::NumpadXor => "Numpad Xor",
+            Key::NumpadPower => "Numpad Power",
+            Key::NumpadPercent => "Numpad Percent",
+            Key::NumpadLess => "Numpad Less",
+            Key::NumpadGreater => "Numpad Greater",
+            Key::NumpadAmpersand => "Numpad Ampersand",
+            Key::NumpadDblAmpersand => "Numpad Dbl Ampersand",
+            Key::NumpadVerticalBar => "Numpad Vertical Bar",
+            Key::NumpadDblVerticalBar => "Numpad Dbl Vertical Bar",
+            Key::NumpadColon => "Numpad Colon",
+            Key::NumpadHash => "Numpad Hash",
+            Key::NumpadSpace => "Numpad Space",
+            Key::NumpadAt => "Numpad At",
+            Key::NumpadExclam => "Numpad Exclam",
+            Key::NumpadMemStore => "Numpad Mem Store",
+            Key::NumpadMemRecall => "Numpad Mem Recall",
+            Key::NumpadMemClear => "Numpad Mem Clear",
+            Key::NumpadMemAdd => "Numpad Mem Add",
+            Key::NumpadMemSubtract => "Numpad Mem Subtract",
+            Key::NumpadMemMultiply => "Numpad Mem Multiply",
+            Key::NumpadMemDivide => "Numpad Mem Divide",
+            Key::NumpadPlusMinus => "Numpad Plus Minus",
+            Key::NumpadClear => "Numpad Clear",
+            Key::NumpadClearEntry => "Numpad Clear Entry",
+            Key::NumpadBinary => "Numpad Binary",
+            Key::NumpadOctal => "Numpad Octal",
+            Key::NumpadDecimal => "Numpad Decimal",
+            Key::NumpadHexadecimal => "Numpad Hexadecimal",
+            Key::LeftCtrl => "Left Ctrl",
+            Key::LeftShift => "Left Shift",
+            Key::LeftAlt => "Left Alt",
+            Key::LeftGui => "Left Gui",
+            Key::RightCtrl => "Right Ctrl",
+            Key::RightShift => "Right Shift",
+            Key::RightAlt => "Right Alt",
+            Key::RightGui => "Right Gui",
+            Key::Mode => "Mode",
+            Key::AudioNext => "Audio Next",
+            Key::AudioPrev => "Audio Prev",
+            Key::AudioStop => "Audio Stop",
+            Key::AudioPlay => "Audio Play",
+            Key::AudioMute => "Audio Mute",
+            Key::MediaSelect => "Media Select",
+            Key::Www => "Www",
+            Key::Mail => "Mail",
+            Key::Calculator => "Calculator",
+            Key::Computer => "Computer",
+            Key::AcSearch => "Ac Search",
+            Key::AcHome => "Ac Home",
+            Key::AcBack => "Ac Back",
+            Key::AcForward => "Ac Forward",
+            Key::AcStop => "Ac Stop",
+            Key::AcRefresh => "Ac Refresh",
+            Key::AcBookmarks => "Ac Bookmarks",
+            Key::BrightnessDown => "Brightness Down",
+            Key::BrightnessUp => "Brightness Up",
+            Key::DisplaySwitch => "Display Switch",
+            Key::KbdIllumToggle => "Kbd Illum Toggle",
+            Key::KbdIllumDown => "Kbd Illum Down",
+            Key::KbdIllumUp => "Kbd Illum Up",
+            Key::Eject => "Eject",
+            Key::Sleep => "Sleep",
+            Key::App1 => "App1",
+            Key::App2 => "App2",
         };
         write!(f, "{val}")
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "button")]
+pub enum Button {
+    A,
+    B,
+    X,
+    Y,
+    Back,
+    Guide,
+    Start,
+    LeftStick,
+    RightStick,
+    LeftShoulder,
+    RightShoulder,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    Misc,
+    Paddle1,
+    Paddle2,
+    Paddle3,
+    Paddle4,
+    Touchpad,
+    LeftStickUp,
+    LeftStickDown,
+    LeftStickLeft,
+    LeftStickRight,
+    RightStickUp,
+    RightStickDown,
+    RightStickLeft,
+    RightStickRight,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl Button {
+    pub fn from_sdl2_button(button: sdl2::controller::Button) -> Button {
+        use sdl2::controller::Button as SdlButton;
+        match button {
+            SdlButton::A => Button::A,
+            SdlButton::B => Button::B,
+            SdlButton::X => Button::X,
+            SdlButton::Y => Button::Y,
+            SdlButton::Back => Button::Back,
+            SdlButton::Guide => Button::Guide,
+            SdlButton::Start => Button::Start,
+            SdlButton::LeftStick => Button::LeftStick,
+            SdlButton::RightStick => Button::RightStick,
+            SdlButton::LeftShoulder => Button::LeftShoulder,
+            SdlButton::RightShoulder => Button::RightShoulder,
+            SdlButton::DPadUp => Button::DPadUp,
+            SdlButton::DPadDown => Button::DPadDown,
+            SdlButton::DPadLeft => Button::DPadLeft,
+            SdlButton::DPadRight => Button::DPadRight,
+            SdlButton::Misc1 => Button::Misc,
+            SdlButton::Paddle1 => Button::Paddle1,
+            SdlButton::Paddle2 => Button::Paddle2,
+            SdlButton::Paddle3 => Button::Paddle3,
+            SdlButton::Paddle4 => Button::Paddle4,
+            SdlButton::Touchpad => Button::Touchpad,
+        }
+    }
+
+    pub fn from_sdl2_axis(
+        axis: sdl2::controller::Axis,
+        value: i16,
+        deadzone: i16,
+    ) -> Vec<(Button, bool)> {
+        use sdl2::controller::Axis;
+        let (negative, positive) = match axis {
+            Axis::LeftX => (Some(Button::LeftStickLeft), Button::LeftStickRight),
+            Axis::LeftY => (Some(Button::LeftStickUp), Button::LeftStickDown),
+            Axis::RightX => (Some(Button::RightStickLeft), Button::RightStickRight),
+            Axis::RightY => (Some(Button::RightStickUp), Button::RightStickDown),
+            Axis::TriggerLeft => (None, Button::LeftTrigger),
+            Axis::TriggerRight => (None, Button::RightTrigger),
+        };
+        let value = value as i32;
+        let deadzone = deadzone as i32;
+        let mut buttons = vec![(positive, value > deadzone)];
+        if let Some(negative) = negative {
+            buttons.push((negative, value < -deadzone));
+        }
+        buttons
+    }
+}
+
+impl std::fmt::Display for Button {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let val = match self {
+            Button::A => "A",
+            Button::B => "B",
+            Button::X => "X",
+            Button::Y => "Y",
+            Button::Back => "Back",
+            Button::Guide => "Guide",
+            Button::Start => "Start",
+            Button::LeftStick => "L3",
+            Button::RightStick => "R3",
+            Button::LeftShoulder => "LB",
+            Button::RightShoulder => "RB",
+            Button::DPadUp => "D-Pad Up",
+            Button::DPadDown => "D-Pad Down",
+            Button::DPadLeft => "D-Pad Left",
+            Button::DPadRight => "D-Pad Right",
+            Button::Misc => "Misc",
+            Button::Paddle1 => "Paddle 1",
+            Button::Paddle2 => "Paddle 2",
+            Button::Paddle3 => "Paddle 3",
+            Button::Paddle4 => "Paddle 4",
+            Button::Touchpad => "Touchpad",
+            Button::LeftStickUp => "Left Stick Up",
+            Button::LeftStickDown => "Left Stick Down",
+            Button::LeftStickLeft => "Left Stick Left",
+            Button::LeftStickRight => "Left Stick Right",
+            Button::RightStickUp => "Right Stick Up",
+            Button::RightStickDown => "Right Stick Down",
+            Button::RightStickLeft => "Right Stick Left",
+            Button::RightStickRight => "Right Stick Right",
+            Button::LeftTrigger => "LT",
+            Button::RightTrigger => "RT",
+        };
+        write!(f, "{val}")
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Binding {
+    Key(Key),
+    Button(Button),
+}
+
+impl std::fmt::Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{key}"),
+            Binding::Button(button) => write!(f, "{button}"),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Timing {
+    pub tick_rate: f64,
+    pub fps_cap: Option<u32>,
+    pub vsync: bool,
+    pub max_steps_per_frame: usize,
+    pub debug_overlay: bool,
+    pub smooth_movement: bool,
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Self {
+            tick_rate: 60.0,
+            fps_cap: None,
+            vsync: true,
+            max_steps_per_frame: 5,
+            debug_overlay: false,
+            smooth_movement: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioBackend {
+    #[default]
+    Auto,
+    Null,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Music {
+    pub tracks: Vec<String>,
+    pub playlist: bool,
+    pub crossfade: f64,
+    pub dynamic: bool,
+    pub mode_tracks: BTreeMap<Mode, Vec<String>>,
+}
+
+impl Default for Music {
+    fn default() -> Self {
+        Self {
+            tracks: vec!["music.ogg".to_string()],
+            playlist: false,
+            crossfade: 2.0,
+            dynamic: true,
+            mode_tracks: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Audio {
+    pub backend: AudioBackend,
+    pub master_volume: u8,
+    pub music_volume: u8,
+    pub sfx_volume: u8,
+    pub music_muted: bool,
+    pub sfx_muted: bool,
+    pub sound_pack: Option<String>,
+    pub music: Music,
+}
+
+impl Default for Audio {
+    fn default() -> Self {
+        Self {
+            backend: AudioBackend::default(),
+            master_volume: 100,
+            music_volume: 100,
+            sfx_volume: 100,
+            music_muted: false,
+            sfx_muted: false,
+            sound_pack: None,
+            music: Music::default(),
+        }
+    }
+}
+
+fn stepped(volume: u8, delta: i16) -> u8 {
+    (volume.min(100) as i16 + delta).clamp(0, 100) as u8
+}
+
+impl Audio {
+    pub fn change_volume(&mut self, delta: i16) {
+        self.master_volume = stepped(self.master_volume, delta);
+    }
+
+    pub fn change_music_volume(&mut self, delta: i16) {
+        self.music_volume = stepped(self.music_volume, delta);
+    }
+
+    pub fn change_sfx_volume(&mut self, delta: i16) {
+        self.sfx_volume = stepped(self.sfx_volume, delta);
+    }
+
+    pub fn toggle_muted(&mut self) {
+        let muted = !(self.music_muted && self.sfx_muted);
+        self.music_muted = muted;
+        self.sfx_muted = muted;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Effects {
+    pub enabled: bool,
+    pub reduced_motion: bool,
+    pub screen_shake: bool,
+    pub particles: bool,
+    pub action_text: bool,
+}
+
+impl Default for Effects {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            reduced_motion: false,
+            screen_shake: true,
+            particles: true,
+            action_text: true,
+        }
+    }
+}
+
+const CONFIG_VERSION: u32 = 2;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Control {
+    Left,
+    Right,
+    SoftDrop,
+    HardDrop,
+    RotateCw,
+    RotateCcw,
+    Swap,
+    Pause,
+    Restart,
+    QuickRestart,
+    ToggleMute,
+    ToggleMusic,
+    ToggleSfx,
+    VolumeUp,
+    VolumeDown,
+    MusicVolumeUp,
+    MusicVolumeDown,
+    SfxVolumeUp,
+    SfxVolumeDown,
+    Screenshot,
+    Options,
+}
+
+impl Control {
+    pub const ALL: [Control; 21] = [
+        Control::Left,
+        Control::Right,
+        Control::SoftDrop,
+        Control::HardDrop,
+        Control::RotateCw,
+        Control::RotateCcw,
+        Control::Swap,
+        Control::Pause,
+        Control::Restart,
+        Control::QuickRestart,
+        Control::ToggleMute,
+        Control::ToggleMusic,
+        Control::ToggleSfx,
+        Control::VolumeUp,
+        Control::VolumeDown,
+        Control::MusicVolumeUp,
+        Control::MusicVolumeDown,
+        Control::SfxVolumeUp,
+        Control::SfxVolumeDown,
+        Control::Screenshot,
+        Control::Options,
+    ];
+}
+
+impl std::fmt::Display for Control {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let val = match self {
+            Control::Left => "left",
+            Control::Right => "right",
+            Control::SoftDrop => "soft drop",
+            Control::HardDrop => "hard drop",
+            Control::RotateCw => "rotate cw",
+            Control::RotateCcw => "rotate ccw",
+            Control::Swap => "swap",
+            Control::Pause => "pause",
+            Control::Restart => "restart",
+            Control::QuickRestart => "quick restart",
+            Control::ToggleMute => "toggle mute",
+            Control::ToggleMusic => "toggle music",
+            Control::ToggleSfx => "toggle sfx",
+            Control::VolumeUp => "volume up",
+            Control::VolumeDown => "volume down",
+            Control::MusicVolumeUp => "music volume up",
+            Control::MusicVolumeDown => "music volume down",
+            Control::SfxVolumeUp => "sfx volume up",
+            Control::SfxVolumeDown => "sfx volume down",
+            Control::Screenshot => "screenshot",
+            Control::Options => "options",
+        };
+        write!(f, "{val}")
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
+    #[serde(default = "Config::legacy_version")]
+    pub version: u32,
     pub reimtris1_feature_parity: bool,
-    pub restart: Vec<Key>,
-    pub left: Vec<Key>,
-    pub right: Vec<Key>,
-    pub rotate_cw: Vec<Key>,
-    pub rotate_ccw: Vec<Key>,
-    pub soft_drop: Vec<Key>,
-    pub hard_drop: Vec<Key>,
-    pub swap: Vec<Key>,
-    pub pause: Vec<Key>,
-    pub toggle_mute: Vec<Key>,
+    pub mode: Mode,
+    pub entry_delay: Option<usize>,
+    pub line_clear_delay: Option<usize>,
+    pub restart: Vec<Binding>,
+    pub quick_restart: Vec<Binding>,
+    pub quick_restart_same_seed: bool,
+    pub resume_countdown: bool,
+    pub left: Vec<Binding>,
+    pub right: Vec<Binding>,
+    pub rotate_cw: Vec<Binding>,
+    pub rotate_ccw: Vec<Binding>,
+    pub soft_drop: Vec<Binding>,
+    pub hard_drop: Vec<Binding>,
+    pub swap: Vec<Binding>,
+    pub pause: Vec<Binding>,
+    pub toggle_mute: Vec<Binding>,
+    pub toggle_music: Vec<Binding>,
+    pub toggle_sfx: Vec<Binding>,
+    pub volume_up: Vec<Binding>,
+    pub volume_down: Vec<Binding>,
+    pub music_volume_up: Vec<Binding>,
+    pub music_volume_down: Vec<Binding>,
+    pub sfx_volume_up: Vec<Binding>,
+    pub sfx_volume_down: Vec<Binding>,
+    pub screenshot: Vec<Binding>,
+    pub options: Vec<Binding>,
+    pub gamepad_deadzone: i16,
+    pub theme: String,
+    pub skin: Option<String>,
+    pub timing: Timing,
+    pub effects: Effects,
+    pub audio: Audio,
+    #[serde(skip)]
+    pub fallback: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             reimtris1_feature_parity: false,
-            restart: vec![Key::Enter, Key::Space],
-            left: vec![Key::Left],
-            right: vec![Key::Right],
-            rotate_cw: vec![Key::X],
-            rotate_ccw: vec![Key::Z],
-            soft_drop: vec![Key::Down],
-            hard_drop: vec![Key::Space],
-            swap: vec![Key::C],
-            pause: vec![Key::P],
-            toggle_mute: vec![Key::M],
+            mode: Mode::default(),
+            entry_delay: None,
+            line_clear_delay: None,
+            restart: vec![
+                Binding::Key(Key::Enter),
+                Binding::Key(Key::Space),
+                Binding::Button(Button::Start),
+            ],
+            quick_restart: vec![Binding::Key(Key::R), Binding::Button(Button::Back)],
+            quick_restart_same_seed: false,
+            resume_countdown: false,
+            left: vec![
+                Binding::Key(Key::Left),
+                Binding::Button(Button::DPadLeft),
+                Binding::Button(Button::LeftStickLeft),
+            ],
+            right: vec![
+                Binding::Key(Key::Right),
+                Binding::Button(Button::DPadRight),
+                Binding::Button(Button::LeftStickRight),
+            ],
+            rotate_cw: vec![Binding::Key(Key::X), Binding::Button(Button::A)],
+            rotate_ccw: vec![Binding::Key(Key::Z), Binding::Button(Button::B)],
+            soft_drop: vec![
+                Binding::Key(Key::Down),
+                Binding::Button(Button::DPadDown),
+                Binding::Button(Button::LeftStickDown),
+            ],
+            hard_drop: vec![Binding::Key(Key::Space), Binding::Button(Button::DPadUp)],
+            swap: vec![
+                Binding::Key(Key::C),
+                Binding::Button(Button::LeftShoulder),
+                Binding::Button(Button::RightShoulder),
+            ],
+            pause: vec![Binding::Key(Key::P), Binding::Button(Button::Start)],
+            toggle_mute: vec![Binding::Key(Key::M), Binding::Button(Button::Y)],
+            toggle_music: vec![Binding::Key(Key::N)],
+            toggle_sfx: vec![Binding::Key(Key::B)],
+            volume_up: vec![Binding::Key(Key::Equals), Binding::Key(Key::NumpadPlus)],
+            volume_down: vec![Binding::Key(Key::Minus), Binding::Key(Key::NumpadMinus)],
+            music_volume_up: vec![Binding::Key(Key::RightBracket)],
+            music_volume_down: vec![Binding::Key(Key::LeftBracket)],
+            sfx_volume_up: vec![Binding::Key(Key::Apostrophe)],
+            sfx_volume_down: vec![Binding::Key(Key::Semicolon)],
+            screenshot: vec![Binding::Key(Key::F12)],
+            options: vec![Binding::Key(Key::O), Binding::Button(Button::Guide)],
+            gamepad_deadzone: 8000,
+            theme: "default".to_string(),
+            skin: None,
+            timing: Timing::default(),
+            effects: Effects::default(),
+            audio: Audio::default(),
+            fallback: false,
         }
     }
 }
 
 impl Config {
-    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Config, String> {
-        let Some(config) = fs::read_to_string(path.as_ref()).ok() else {
-            let config = Config::default();
-            {
+    fn legacy_version() -> u32 {
+        1
+    }
+
+    fn fallback() -> Self {
+        Self {
+            fallback: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn bindings(&self, control: Control) -> &Vec<Binding> {
+        match control {
+            Control::Left => &self.left,
+            Control::Right => &self.right,
+            Control::SoftDrop => &self.soft_drop,
+            Control::HardDrop => &self.hard_drop,
+            Control::RotateCw => &self.rotate_cw,
+            Control::RotateCcw => &self.rotate_ccw,
+            Control::Swap => &self.swap,
+            Control::Pause => &self.pause,
+            Control::Restart => &self.restart,
+            Control::QuickRestart => &self.quick_restart,
+            Control::ToggleMute => &self.toggle_mute,
+            Control::ToggleMusic => &self.toggle_music,
+            Control::ToggleSfx => &self.toggle_sfx,
+            Control::VolumeUp => &self.volume_up,
+            Control::VolumeDown => &self.volume_down,
+            Control::MusicVolumeUp => &self.music_volume_up,
+            Control::MusicVolumeDown => &self.music_volume_down,
+            Control::SfxVolumeUp => &self.sfx_volume_up,
+            Control::SfxVolumeDown => &self.sfx_volume_down,
+            Control::Screenshot => &self.screenshot,
+            Control::Options => &self.options,
+        }
+    }
+
+    pub fn bindings_mut(&mut self, control: Control) -> &mut Vec<Binding> {
+        match control {
+            Control::Left => &mut self.left,
+            Control::Right => &mut self.right,
+            Control::SoftDrop => &mut self.soft_drop,
+            Control::HardDrop => &mut self.hard_drop,
+            Control::RotateCw => &mut self.rotate_cw,
+            Control::RotateCcw => &mut self.rotate_ccw,
+            Control::Swap => &mut self.swap,
+            Control::Pause => &mut self.pause,
+            Control::Restart => &mut self.restart,
+            Control::QuickRestart => &mut self.quick_restart,
+            Control::ToggleMute => &mut self.toggle_mute,
+            Control::ToggleMusic => &mut self.toggle_music,
+            Control::ToggleSfx => &mut self.toggle_sfx,
+            Control::VolumeUp => &mut self.volume_up,
+            Control::VolumeDown => &mut self.volume_down,
+            Control::MusicVolumeUp => &mut self.music_volume_up,
+            Control::MusicVolumeDown => &mut self.music_volume_down,
+            Control::SfxVolumeUp => &mut self.sfx_volume_up,
+            Control::SfxVolumeDown => &mut self.sfx_volume_down,
+            Control::Screenshot => &mut self.screenshot,
+            Control::Options => &mut self.options,
+        }
+    }
+
+    pub fn bound(&self, binding: &Binding) -> bool {
+        Control::ALL
+            .into_iter()
+            .any(|control| self.bindings(control).contains(binding))
+    }
+
+    pub fn conflicts(&self) -> Vec<(&Binding, Vec<Control>)> {
+        let mut conflicts: Vec<(&Binding, Vec<Control>)> = Vec::new();
+        for control in Control::ALL {
+            for binding in self.bindings(control) {
+                match conflicts.iter_mut().find(|(other, _)| *other == binding) {
+                    Some((_, controls)) => controls.push(control),
+                    None => conflicts.push((binding, vec![control])),
+                }
+            }
+        }
+        conflicts.retain(|(_, controls)| controls.len() > 1);
+        conflicts
+    }
+
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), String> {
+        if self.fallback {
+            return Err(format!(
+                "'{}' could not be loaded, not overwriting it",
+                path.as_ref().display()
+            ));
+        }
+        let config = toml::to_string(self).map_err(|err| err.to_string())?;
+        fs::write(path.as_ref(), config).map_err(|err| err.to_string())
+    }
+
+    pub fn actions(&self, binding: &Binding) -> Vec<Action> {
+        [
+            (&self.left, Action::Left),
+            (&self.right, Action::Right),
+            (&self.soft_drop, Action::SoftDrop),
+            (&self.hard_drop, Action::HardDrop),
+            (&self.rotate_cw, Action::RotateCw),
+            (&self.rotate_ccw, Action::RotateCcw),
+            (&self.swap, Action::Swap),
+        ]
+        .into_iter()
+        .filter(|(bindings, _)| bindings.contains(binding))
+        .map(|(_, action)| action)
+        .collect()
+    }
+
+    pub fn settings(&self, mode: Mode) -> Settings {
+        let mut settings = Settings::new(mode);
+        if let Some(entry_delay) = self.entry_delay {
+            settings.entry_delay = entry_delay;
+        }
+        if let Some(line_clear_delay) = self.line_clear_delay {
+            settings.line_clear_delay = line_clear_delay;
+        }
+        settings
+    }
+
+    fn migrate(&mut self) {
+        let default = Config::default();
+        if self.version < 2 {
+            for (bindings, defaults) in [
+                (&mut self.restart, &default.restart),
+                (&mut self.left, &default.left),
+                (&mut self.right, &default.right),
+                (&mut self.rotate_cw, &default.rotate_cw),
+                (&mut self.rotate_ccw, &default.rotate_ccw),
+                (&mut self.soft_drop, &default.soft_drop),
+                (&mut self.hard_drop, &default.hard_drop),
+                (&mut self.swap, &default.swap),
+                (&mut self.pause, &default.pause),
+                (&mut self.toggle_mute, &default.toggle_mute),
+            ] {
+                if bindings.iter().any(|v| matches!(v, Binding::Button(_))) {
+                    continue;
+                }
+                let buttons = defaults
+                    .iter()
+                    .filter(|v| matches!(v, Binding::Button(_)))
+                    .cloned();
+                bindings.extend(buttons);
+            }
+        }
+        self.version = CONFIG_VERSION;
+    }
+
+    pub fn parse(text: &str) -> Result<(Config, Vec<String>), String> {
+        let mut notices = Vec::new();
+        let deserializer = toml::Deserializer::new(text);
+        let mut config: Config = serde_ignored::deserialize(deserializer, |path| {
+            notices.push(format!("config: ignoring unknown option '{path}'"))
+        })
+        .map_err(|err| err.to_string())?;
+
+        if config.version > CONFIG_VERSION {
+            notices.push(format!(
+                "config: version {} is newer than this build supports ({CONFIG_VERSION})",
+                config.version
+            ));
+        }
+        config.migrate();
+
+        let default = Timing::default();
+        if !(config.timing.tick_rate.is_finite() && config.timing.tick_rate > 0.0) {
+            notices.push(format!(
+                "config: timing.tick_rate must be positive, using {}",
+                default.tick_rate
+            ));
+            config.timing.tick_rate = default.tick_rate;
+        }
+        if config.timing.fps_cap == Some(0) {
+            notices
+                .push("config: timing.fps_cap must be positive, leaving fps uncapped".to_string());
+            config.timing.fps_cap = default.fps_cap;
+        }
+
+        Ok((config, notices))
+    }
+
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<(Config, Vec<String>), String> {
+        let path = path.as_ref();
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                let config = Config::default();
                 println!("could not get config! creating default...");
-                let config = toml::to_string(&config).map_err(|err| err.to_string())?;
-                fs::write(path.as_ref(), config).map_err(|err| err.to_string())?;
-                println!("created config at '{}'", path.as_ref().display());
+                config.save(path)?;
+                println!("created config at '{}'", path.display());
+                return Ok((config, Vec::new()));
+            }
+            Err(err) => {
+                let notice = format!(
+                    "womp womp, could not read '{}', using defaults until it is fixed:\n{err}",
+                    path.display()
+                );
+                eprintln!("{notice}");
+                return Ok((Config::fallback(), vec![notice]));
             }
-            return Ok(config);
         };
-        let Some(config) = toml::from_str(&config).ok() else {
-            println!("womp womp, config contains an invalid config, resetting...");
-            let config = Config::default();
-            {
-                let config = toml::to_string(&config).map_err(|err| err.to_string())?;
-                fs::write(path.as_ref(), config).map_err(|err| err.to_string())?;
-                println!("created config at '{}'", path.as_ref().display());
+
+        let (config, mut notices) = match Config::parse(&text) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                let notice = format!(
+                    "womp womp, '{}' is invalid, using defaults until it is fixed:\n{err}",
+                    path.display()
+                );
+                eprintln!("{notice}");
+                return Ok((Config::fallback(), vec![notice]));
             }
-            return Ok(config);
         };
-        Ok(config)
+
+        let version = toml::from_str::<toml::Table>(&text)
+            .ok()
+            .and_then(|table| table.get("version")?.as_integer())
+            .unwrap_or(Config::legacy_version() as i64);
+        if version < CONFIG_VERSION as i64 {
+            let backup = path.with_extension(format!("toml.v{version}.bak"));
+            fs::copy(path, &backup).map_err(|err| err.to_string())?;
+            config.save(path)?;
+            notices.push(format!(
+                "config: migrated to version {CONFIG_VERSION}, old file backed up to '{}'",
+                backup.display()
+            ));
+        }
+
+        for notice in &notices {
+            eprintln!("{notice}");
+        }
+        Ok((config, notices))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Binding, Button, Config, Control, Key};
+
+    #[test]
+    fn partial_config_keeps_defaults() {
+        let (config, notices) = Config::parse(
+            "
+            version = 2
+            left = [{ key = \"A\" }]
+            ",
+        )
+        .unwrap();
+        assert!(notices.is_empty());
+        assert_eq!(config.left, vec![Binding::Key(Key::A)]);
+        assert_eq!(config.right, Config::default().right);
+    }
+
+    #[test]
+    fn unknown_options_and_errors_are_reported() {
+        let (_, notices) = Config::parse("version = 2\nfoo = 1").unwrap();
+        assert_eq!(notices, vec!["config: ignoring unknown option 'foo'"]);
+
+        let err = Config::parse("version = 2\nleft = [{ key = \"Nope\" }]").unwrap_err();
+        assert!(err.contains("line 2"), "{err}");
+    }
+
+    #[test]
+    fn invalid_timing_falls_back_to_defaults() {
+        let (config, notices) =
+            Config::parse("version = 2\n[timing]\ntick_rate = 0.0\nfps_cap = 0").unwrap();
+        assert_eq!(notices.len(), 2);
+        assert_eq!(config.timing.tick_rate, 60.0);
+        assert_eq!(config.timing.fps_cap, None);
+    }
+
+    #[test]
+    fn legacy_config_gains_gamepad_bindings() {
+        let (config, _) = Config::parse("left = [{ key = \"Left\" }]").unwrap();
+        assert_eq!(config.version, 2);
+        assert!(config.left.contains(&Binding::Button(Button::DPadLeft)));
+    }
+
+    #[test]
+    fn default_bindings_report_conflicts() {
+        let config = Config::default();
+        let conflicts = config.conflicts();
+        assert!(conflicts.contains(&(
+            &Binding::Key(Key::Space),
+            vec![Control::HardDrop, Control::Restart]
+        )));
+    }
+
+    #[test]
+    fn keys_and_buttons_round_trip() {
+        let config = toml::to_string(&Config::default()).unwrap();
+        let config: Config = toml::from_str(&config).unwrap();
+        assert!(config.left.contains(&Binding::Key(Key::Left)));
+        assert!(config.left.contains(&Binding::Button(Button::DPadLeft)));
+    }
+
+    #[test]
+    fn volume_is_clamped_and_mutes_persist() {
+        let mut config = Config::default();
+        config.audio.change_volume(10);
+        assert_eq!(config.audio.master_volume, 100);
+        config.audio.change_volume(-30);
+        assert_eq!(config.audio.master_volume, 70);
+        config.audio.change_music_volume(-110);
+        config.audio.change_sfx_volume(-10);
+        assert_eq!(config.audio.music_volume, 0);
+        assert_eq!(config.audio.sfx_volume, 90);
+
+        config.audio.music_muted = true;
+        config.audio.toggle_muted();
+        assert!(config.audio.music_muted && config.audio.sfx_muted);
+
+        let (config, _) = Config::parse(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(config.audio.master_volume, 70);
+        assert_eq!(config.audio.sfx_volume, 90);
+        assert!(config.audio.music_muted && config.audio.sfx_muted);
+    }
+
+    #[test]
+    fn unreadable_config_is_not_overwritten() {
+        let dir = std::env::temp_dir().join(format!("reimtris2-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("binary.toml");
+        std::fs::write(&path, [0xff, 0xfe, 0x00]).unwrap();
+        let (config, notices) = Config::from_file(&path).unwrap();
+        assert_eq!(notices.len(), 1);
+        assert!(config.save(&path).is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), [0xff, 0xfe, 0x00]);
+
+        let path = dir.join("invalid.toml");
+        std::fs::write(&path, "left = 1").unwrap();
+        let (config, _) = Config::from_file(&path).unwrap();
+        assert!(config.save(&path).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "left = 1");
+
+        let path = dir.join("missing.toml");
+        Config::from_file(&path).unwrap();
+        assert!(Config::parse(&std::fs::read_to_string(&path).unwrap()).is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn sdl2_scancodes_round_trip() {
+        use sdl2::keyboard::Scancode;
+
+        // from_i32 transmutes, so only feed it values SDL defines
+        let defined = [4..=129, 133..=164, 176..=221, 224..=231, 257..=290];
+        let mut keys = Vec::new();
+        for scancode in defined.into_iter().flatten().filter_map(Scancode::from_i32) {
+            if let Some(key) = Key::from_sdl2_scancode(scancode) {
+                assert_eq!(key.to_sdl2_scancode(), scancode, "{key}");
+                keys.push(key);
+            }
+        }
+        assert!(keys.contains(&Key::Escape) && keys.contains(&Key::Apostrophe));
+        for key in keys {
+            assert_eq!(Key::from_sdl2_scancode(key.to_sdl2_scancode()), Some(key));
+        }
+
+        let mut config = Config::default();
+        assert!(!config.bound(&Binding::Key(Key::Escape)));
+        config.pause.push(Binding::Key(Key::Escape));
+        assert!(config.bound(&Binding::Key(Key::Escape)));
     }
 }
@@ -1,12 +1,23 @@
 use crate::actions::{Action, ActionsHeld};
 use crate::board::Board;
-use crate::tetromino::{Direction, DirectionDiff, Tetromino};
+use crate::mode::{Mode, Settings};
+use crate::randomizer::Randomizer;
+use crate::scoring::{Lock, Score, ScoringRules, Spin};
+use crate::tetromino::{Direction, DirectionDiff, RotationSystem, Tetromino};
 
 pub enum SoundEffect {
     HardDrop,
+    Lock,
     LineClear(usize),
     Move,
     Rotation,
+    Hold,
+    LevelUp,
+    TSpin,
+    PerfectClear,
+    Combo(usize),
+    GameOver,
+    Countdown,
 }
 
 #[derive(Debug)]
@@ -18,9 +29,10 @@ pub struct CurrentTetromino {
 }
 
 impl CurrentTetromino {
-    fn new(tetromino: Tetromino) -> Self {
+    fn new(tetromino: Tetromino, rotation_system: RotationSystem) -> Self {
+        let direction = rotation_system.spawn_direction();
         let width = tetromino
-            .pattern(&Direction::Up)
+            .pattern(&direction)
             .into_iter()
             .map(|(x, _y)| x)
             .max()
@@ -28,7 +40,7 @@ impl CurrentTetromino {
             + 1;
 
         let height = tetromino
-            .pattern(&Direction::Up)
+            .pattern(&direction)
             .into_iter()
             .map(|(_x, y)| y)
             .max()
@@ -38,87 +50,81 @@ impl CurrentTetromino {
         let x = ((Board::WIDTH - width) / 2) as i8;
         Self {
             tetromino,
-            direction: Direction::Up,
+            direction,
             x,
             y: -(height as i8),
         }
     }
 }
 
+pub enum Phase {
+    Falling,
+    LineClear { rows: Vec<usize>, remaining: usize },
+    Entry { remaining: usize },
+}
+
 pub struct Game {
     pub game_over: bool,
+    pub phase: Phase,
     pub board: Board,
     pub next_tetrominos: [Tetromino; 3],
-    bag: Bag,
+    randomizer: Box<dyn Randomizer>,
     pub current_tetromino: CurrentTetromino,
     pub held_tetromino: Option<Tetromino>,
     has_swapped_held: bool,
+    last_rotation_kick: Option<usize>,
+    pub mode: Mode,
+    pub seed: u64,
+    settings: Settings,
+    rules: Box<dyn ScoringRules>,
     pub score: Score,
     pub ticks: usize,
 }
 
-struct Bag {
-    inner: [Tetromino; 7],
-    idx: usize,
-}
-
-impl Bag {
-    fn new() -> Self {
-        Self {
-            inner: Self::random_tetrominos(),
-            idx: 0,
-        }
-    }
-    pub fn random_tetrominos() -> [Tetromino; 7] {
-        use rand::seq::IndexedRandom;
-        let sample = [
-            Tetromino::I,
-            Tetromino::J,
-            Tetromino::L,
-            Tetromino::O,
-            Tetromino::S,
-            Tetromino::T,
-            Tetromino::Z,
-        ];
-
-        debug_assert_eq!(sample.len(), 7, "each piece should only appear once");
-
-        sample
-            .choose_multiple_array(&mut rand::rng())
-            .expect("both arrays should have a length of 7")
-    }
-    fn take_next(&mut self) -> Tetromino {
-        if self.idx >= self.inner.len() {
-            self.idx = 0;
-            self.inner = Self::random_tetrominos();
-        }
-
-        let uninitialized_tetromino = Tetromino::I;
-        let current = std::mem::replace(&mut self.inner[self.idx], uninitialized_tetromino);
-        self.idx += 1;
-        current
-    }
-}
-
 impl Game {
-    pub fn new() -> Self {
-        let mut bag = Bag::new();
+    pub fn new(settings: Settings) -> Self {
+        let mode = settings.mode;
+        let seed = settings.seed.unwrap_or_else(rand::random);
+        let mut randomizer = mode.randomizer(seed);
+        let rules = mode.scoring_rules();
+        let level = settings.level.unwrap_or_else(|| rules.initial_level());
 
         Self {
             game_over: false,
+            phase: Phase::Falling,
             board: Board::new(),
-            next_tetrominos: std::array::from_fn(|_| bag.take_next()),
-            current_tetromino: CurrentTetromino::new(bag.take_next()),
+            next_tetrominos: std::array::from_fn(|_| randomizer.take_next()),
+            current_tetromino: CurrentTetromino::new(
+                randomizer.take_next(),
+                mode.rotation_system(),
+            ),
             held_tetromino: None,
-            bag,
+            randomizer,
             has_swapped_held: false,
-            score: Score::new(),
+            last_rotation_kick: None,
+            mode,
+            seed,
+            settings,
+            score: Score::new(level),
+            rules,
             ticks: 0,
         }
     }
 
+    pub fn reset(&mut self, same_seed: bool) {
+        let mut settings = self.settings.clone();
+        if same_seed {
+            settings.seed = Some(self.seed);
+        }
+        *self = Game::new(settings);
+    }
+
+    pub fn grade(&self) -> Option<&'static str> {
+        self.rules.grade(&self.score)
+    }
+
     fn take_next_up(&mut self) -> Tetromino {
-        let mut last = self.bag.take_next();
+        let mut last = self.randomizer.take_next();
         for value in self.next_tetrominos.iter_mut().rev() {
             std::mem::swap(value, &mut last)
         }
@@ -126,7 +132,7 @@ impl Game {
     }
 
     fn try_hard_drop(&mut self, actions: &ActionsHeld, effects: &mut Vec<SoundEffect>) {
-        if !actions.just_pressed(self.ticks, &Action::HardDrop) {
+        if !self.mode.hard_drop() || !actions.just_pressed(self.ticks, &Action::HardDrop) {
             return;
         }
         let start_y = self.current_tetromino.y;
@@ -136,37 +142,46 @@ impl Game {
                 continue;
             }
             self.current_tetromino.y -= 1;
-            self.score.points += (self.current_tetromino.y - start_y) as usize * 2;
-            self.place_current_tetromino();
-            self.check_line_clears(effects);
+            let rows = (self.current_tetromino.y - start_y) as usize;
+            if rows > 0 {
+                self.last_rotation_kick = None;
+            }
+            self.rules.hard_drop(&mut self.score, rows);
+            self.lock_current_tetromino(true, effects);
             break;
         }
     }
 
     fn soft_drop(&mut self, actions: &ActionsHeld, effects: &mut Vec<SoundEffect>) {
-        let mut delay = 32 - self.score.level * 2;
-        if actions.contains_key(&Action::SoftDrop) {
-            delay /= 10;
-        }
+        let delay = if actions.contains_key(&Action::SoftDrop) {
+            self.mode.soft_drop_gravity(self.score.level)
+        } else {
+            self.mode.gravity(self.score.level)
+        };
 
-        if self.ticks % delay != 0 {
+        if !self.ticks.is_multiple_of(delay) {
             return;
         }
 
         self.current_tetromino.y += 1;
         if self.board.colliding(&self.current_tetromino) {
             self.current_tetromino.y -= 1;
-            self.place_current_tetromino();
-            self.check_line_clears(effects);
-        } else if actions.contains_key(&Action::SoftDrop) {
-            self.score.points += 1;
+            self.lock_current_tetromino(false, effects);
+            return;
+        }
+        self.last_rotation_kick = None;
+        if actions.contains_key(&Action::SoftDrop) {
+            self.rules.soft_drop(&mut self.score, 1);
         }
     }
 
     fn try_move_horizontally(&mut self, actions: &ActionsHeld, effects: &mut Vec<SoundEffect>) {
+        let das = self.mode.das();
         for key in [Action::Left, Action::Right] {
             let just_pressed = actions.just_pressed(self.ticks, &key);
-            let long_press = actions.held_for(self.ticks, &key, |held_for| held_for > 15);
+            let long_press = actions.held_for(self.ticks, &key, |held_for| {
+                held_for >= das.delay && (held_for - das.delay).is_multiple_of(das.repeat)
+            });
             if !just_pressed && !long_press {
                 continue;
             }
@@ -179,38 +194,135 @@ impl Game {
             if self.board.colliding(&self.current_tetromino) {
                 self.current_tetromino.x -= offset;
             } else {
+                self.last_rotation_kick = None;
                 effects.push(SoundEffect::Move);
             }
         }
     }
 
-    fn check_line_clears(&mut self, effects: &mut Vec<SoundEffect>) {
-        let lines_cleared = self.board.lines_cleared();
-
-        self.score.level_up(lines_cleared);
+    fn lock_current_tetromino(&mut self, hard_drop: bool, effects: &mut Vec<SoundEffect>) {
+        let spin = self.spin();
+        self.place_current_tetromino();
+        if self.game_over {
+            effects.push(SoundEffect::GameOver);
+        }
 
-        let mut points =
-            self.score.level as f32 * Score::point_multiplier_from_lines_cleared(lines_cleared);
+        let rows = self.board.full_rows();
+        let lines_cleared = rows.len();
+        let perfect_clear = lines_cleared > 0
+            && self
+                .board
+                .iter()
+                .all(|row| !row.contains(&None) || row.iter().all(|tile| tile.is_none()));
+
+        let scored = self.rules.lock(
+            &mut self.score,
+            &Lock {
+                lines_cleared,
+                spin,
+                perfect_clear,
+            },
+        );
 
-        if self.score.back_to_back && lines_cleared == 4 {
-            points *= 1.5;
+        if lines_cleared > 0 {
+            effects.push(SoundEffect::LineClear(lines_cleared));
+        } else if hard_drop {
+            effects.push(SoundEffect::HardDrop);
+        } else {
+            effects.push(SoundEffect::Lock);
+        }
+        if spin != Spin::None {
+            effects.push(SoundEffect::TSpin);
+        }
+        if perfect_clear {
+            effects.push(SoundEffect::PerfectClear);
+        }
+        if let Some(combo) = scored.combo {
+            effects.push(SoundEffect::Combo(combo));
+        }
+        if scored.level_up {
+            effects.push(SoundEffect::LevelUp);
         }
-        points += self.score.combos(lines_cleared) as f32;
 
-        self.score.points += points as usize;
+        self.phase = if lines_cleared > 0 && self.settings.line_clear_delay > 0 {
+            Phase::LineClear {
+                rows,
+                remaining: self.settings.line_clear_delay,
+            }
+        } else {
+            self.board.clear_rows(&rows);
+            self.entry_phase()
+        };
+    }
 
-        if lines_cleared == 4 {
-            self.score.back_to_back = true;
-        } else if lines_cleared > 0 {
-            self.score.back_to_back = false;
+    fn entry_phase(&self) -> Phase {
+        if self.settings.entry_delay > 0 {
+            Phase::Entry {
+                remaining: self.settings.entry_delay,
+            }
+        } else {
+            Phase::Falling
         }
+    }
 
-        if lines_cleared > 0 {
-            self.score.combo += 1;
-            effects.push(SoundEffect::LineClear(lines_cleared));
+    fn step_delay(&mut self) {
+        match &mut self.phase {
+            Phase::Falling => {}
+            Phase::LineClear { rows, remaining } => {
+                *remaining -= 1;
+                if *remaining == 0 {
+                    let rows = std::mem::take(rows);
+                    self.board.clear_rows(&rows);
+                    self.phase = self.entry_phase();
+                }
+            }
+            Phase::Entry { remaining } => {
+                *remaining -= 1;
+                if *remaining == 0 {
+                    self.phase = Phase::Falling;
+                }
+            }
+        }
+    }
+
+    fn spin(&self) -> Spin {
+        let CurrentTetromino {
+            tetromino: Tetromino::T,
+            direction,
+            x,
+            y,
+        } = &self.current_tetromino
+        else {
+            return Spin::None;
+        };
+        let Some(kick) = self.last_rotation_kick else {
+            return Spin::None;
+        };
+
+        let occupied = |(x_offset, y_offset): (i8, i8)| {
+            let x = x + x_offset;
+            let y = y + y_offset;
+            if x < 0 || x >= Board::WIDTH as i8 || y >= Board::HEIGHT as i8 {
+                return true;
+            }
+            y >= 0 && self.board[y as usize][x as usize].is_some()
+        };
+
+        let (front, back) = match direction {
+            Direction::Up => ([(0, 1), (2, 1)], [(0, 3), (2, 3)]),
+            Direction::Right => ([(2, 1), (2, 3)], [(0, 1), (0, 3)]),
+            Direction::Down => ([(0, 3), (2, 3)], [(0, 1), (2, 1)]),
+            Direction::Left => ([(0, 1), (0, 3)], [(2, 1), (2, 3)]),
+        };
+        let front = front.into_iter().filter(|&c| occupied(c)).count();
+        let back = back.into_iter().filter(|&c| occupied(c)).count();
+
+        if front + back < 3 {
+            Spin::None
+        } else if front == 2 || kick == 4 {
+            Spin::Full
         } else {
-            self.score.combo = 0;
-            effects.push(SoundEffect::HardDrop);
+            Spin::Mini
         }
     }
 
@@ -219,11 +331,24 @@ impl Game {
             panic!("should check if game is over before stepping");
         }
         let mut effects = Vec::new();
+        if !matches!(self.phase, Phase::Falling) {
+            self.step_delay();
+            self.ticks += 1;
+            return effects;
+        }
         self.try_hard_drop(actions, &mut effects);
+        if !matches!(self.phase, Phase::Falling) {
+            self.ticks += 1;
+            return effects;
+        }
         self.soft_drop(actions, &mut effects);
+        if !matches!(self.phase, Phase::Falling) {
+            self.ticks += 1;
+            return effects;
+        }
         self.try_move_horizontally(actions, &mut effects);
 
-        if actions.just_pressed(self.ticks, &Action::Swap) {
+        if self.mode.hold() && actions.just_pressed(self.ticks, &Action::Swap) {
             self.try_swap_tetromino(&mut effects);
         }
 
@@ -241,21 +366,32 @@ impl Game {
     }
 
     fn try_rotate(&mut self, diff: DirectionDiff, effects: &mut Vec<SoundEffect>) {
-        let rotated = self.current_tetromino.direction.rotate(&diff);
+        let rotation_system = self.mode.rotation_system();
+        let rotated = rotation_system.rotate(
+            &self.current_tetromino.tetromino,
+            &self.current_tetromino.direction,
+            &diff,
+        );
         let old_direction = std::mem::replace(&mut self.current_tetromino.direction, rotated);
         if !self.board.colliding(&self.current_tetromino) {
+            self.last_rotation_kick = Some(0);
             effects.push(SoundEffect::Rotation);
             return;
         }
+        if !rotation_system.wall_kicks() {
+            self.current_tetromino.direction = old_direction;
+            return;
+        }
         let wall_kicks = self
             .current_tetromino
             .tetromino
             .wall_kicks(&old_direction, &diff);
 
-        for (x, y) in wall_kicks {
+        for (kick, (x, y)) in wall_kicks.into_iter().enumerate() {
             self.current_tetromino.x += x;
             self.current_tetromino.y += y;
             if !(self.board.colliding(&self.current_tetromino)) {
+                self.last_rotation_kick = Some(kick);
                 effects.push(SoundEffect::Rotation);
                 return;
             }
@@ -267,7 +403,7 @@ impl Game {
     }
 
     fn place_current_tetromino(&mut self) {
-        let next = CurrentTetromino::new(self.take_next_up());
+        let next = CurrentTetromino::new(self.take_next_up(), self.mode.rotation_system());
         let current = std::mem::replace(&mut self.current_tetromino, next);
         let pattern = current.tetromino.pattern(&current.direction);
 
@@ -293,6 +429,7 @@ impl Game {
         }
 
         self.has_swapped_held = false;
+        self.last_rotation_kick = None;
     }
 
     fn try_swap_tetromino(&mut self, effects: &mut Vec<SoundEffect>) {
@@ -300,60 +437,75 @@ impl Game {
             return;
         }
         self.has_swapped_held = true;
+        self.last_rotation_kick = None;
         let held_or_first_in_bag_tetromino = self
             .held_tetromino
             .take()
             .unwrap_or_else(|| self.take_next_up());
-        let current_tetromino = CurrentTetromino::new(held_or_first_in_bag_tetromino);
+        let current_tetromino =
+            CurrentTetromino::new(held_or_first_in_bag_tetromino, self.mode.rotation_system());
         let old_tetromino = std::mem::replace(&mut self.current_tetromino, current_tetromino);
         self.held_tetromino.replace(old_tetromino.tetromino);
-        effects.push(SoundEffect::Rotation);
+        effects.push(SoundEffect::Hold);
     }
 }
 
-pub struct Score {
-    pub level: usize,
-    pub points: usize,
-    pub lines: usize,
-    pub combo: usize,
-    back_to_back: bool,
-}
-
-impl Score {
-    const fn new() -> Self {
-        Self {
-            level: 0,
-            points: 0,
-            lines: 0,
-            combo: 0,
-            back_to_back: false,
-        }
-    }
-
-    fn level_up(&mut self, lines_cleared: usize) {
-        self.lines += lines_cleared;
-        if self.lines > self.level * 5 {
-            self.level += 1;
-            self.lines = 0;
-        }
-    }
-
-    fn point_multiplier_from_lines_cleared(lines_cleared: usize) -> f32 {
-        match lines_cleared {
-            0 => 0.0,
-            1 => 100.0,
-            2 => 300.0,
-            3 => 500.0,
-            4 => 800.0,
-            _ => unreachable!("we cannot clear more than 4 lines"),
+#[cfg(test)]
+mod test {
+    use super::{CurrentTetromino, Game, Phase};
+    use crate::actions::{Action, ActionsHeld};
+    use crate::board::Board;
+    use crate::mode::{Mode, Settings};
+    use crate::tetromino::{Direction, Tetromino};
+
+    #[test]
+    fn cleared_rows_stay_until_line_clear_delay_passes() {
+        let mut settings = Settings::new(Mode::Reimtris);
+        settings.entry_delay = 2;
+        settings.line_clear_delay = 3;
+        let mut game = Game::new(settings);
+
+        for x in 4..Board::WIDTH {
+            game.board[Board::HEIGHT - 1][x] = Some(Tetromino::O);
         }
+        game.current_tetromino = CurrentTetromino {
+            tetromino: Tetromino::I,
+            direction: Direction::Up,
+            x: 0,
+            y: 0,
+        };
+
+        let mut actions = ActionsHeld::new();
+        actions.insert(Action::HardDrop, 0);
+        let next = game.next_tetrominos[0].clone();
+        let spawn = CurrentTetromino::new(next, game.mode.rotation_system());
+        game.step(&actions);
+        assert!(matches!(game.phase, Phase::LineClear { .. }));
+        assert!(!game.board[Board::HEIGHT - 1].contains(&None));
+        assert_eq!(game.current_tetromino.y, spawn.y);
+
+        game.step(&actions);
+        game.step(&actions);
+        assert!(!game.board[Board::HEIGHT - 1].contains(&None));
+        game.step(&actions);
+        assert!(matches!(game.phase, Phase::Entry { remaining: 2 }));
+        assert!(game.board[Board::HEIGHT - 1].iter().all(Option::is_none));
+
+        game.step(&actions);
+        game.step(&actions);
+        assert!(matches!(game.phase, Phase::Falling));
     }
 
-    fn combos(&self, lines_cleared: usize) -> usize {
-        if lines_cleared > 0 {
-            self.combo * 50 * self.level
-        } else {
-            0
-        }
+    #[test]
+    fn reset_with_same_seed_replays_the_same_pieces() {
+        let mut game = Game::new(Settings::new(Mode::Reimtris));
+        let seed = game.seed;
+        let next = game.next_tetrominos.clone();
+        game.score.points = 1000;
+
+        game.reset(true);
+        assert_eq!(game.seed, seed);
+        assert_eq!(game.next_tetrominos, next);
+        assert_eq!(game.score.points, 0);
     }
 }
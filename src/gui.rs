@@ -1,5 +1,22 @@
+mod assets;
 mod audio;
+mod cache;
+mod effects;
+mod export;
+mod layout;
+mod motion;
+mod options;
+mod raster;
 mod sdl;
+mod session;
+mod skin;
+mod software;
+mod sound_pack;
+mod theme;
+mod timestep;
+mod tui;
 mod ui;
 
+pub use export::export_replay;
 pub use sdl::start_game;
+pub use tui::start_terminal_game;
@@ -0,0 +1,98 @@
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+pub const FONT: &str = "josenfin_sans_regular.ttf";
+
+const SYSTEM_DIR: &str = match option_env!("REIMTRIS2_SYSTEM_DIR") {
+    Some(dir) => dir,
+    None => "/usr/share/reimtris2",
+};
+
+const EMBEDDED: [(&str, &[u8]); 6] = [
+    (
+        "hard_drop.ogg",
+        include_bytes!("../../resources/hard_drop.ogg"),
+    ),
+    (
+        "josenfin_sans_regular.ttf",
+        include_bytes!("../../resources/josenfin_sans_regular.ttf"),
+    ),
+    (
+        "line_clear.ogg",
+        include_bytes!("../../resources/line_clear.ogg"),
+    ),
+    ("move.ogg", include_bytes!("../../resources/move.ogg")),
+    ("music.ogg", include_bytes!("../../resources/music.ogg")),
+    (
+        "rotation.ogg",
+        include_bytes!("../../resources/rotation.ogg"),
+    ),
+];
+
+#[derive(Clone)]
+pub struct Assets {
+    dirs: Vec<PathBuf>,
+}
+
+impl Assets {
+    pub fn new() -> Result<Self, String> {
+        let base = xdg::BaseDirectories::with_prefix("reimtris2").map_err(|err| err.to_string())?;
+        Ok(Self::with_dirs(vec![
+            base.get_data_home(),
+            PathBuf::from(SYSTEM_DIR),
+        ]))
+    }
+
+    pub fn with_dirs(dirs: Vec<PathBuf>) -> Self {
+        Self { dirs }
+    }
+
+    pub fn load(&self, name: &str) -> Result<Cow<'static, [u8]>, String> {
+        for dir in &self.dirs {
+            let path = dir.join(name);
+            if path.is_file() {
+                return std::fs::read(&path)
+                    .map(Cow::Owned)
+                    .map_err(|err| format!("could not read asset '{}': {err}", path.display()));
+            }
+        }
+
+        if let Some((_, bytes)) = EMBEDDED.iter().find(|(embedded, _)| *embedded == name) {
+            return Ok(Cow::Borrowed(bytes));
+        }
+
+        let searched = self
+            .dirs
+            .iter()
+            .map(|dir| format!("\n    {}", dir.join(name).display()))
+            .collect::<String>();
+        Err(format!(
+            "could not find asset '{name}', searched:{searched}\n    (embedded assets)"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Assets;
+    use std::borrow::Cow;
+
+    #[test]
+    fn prefers_override_dir_then_embedded() {
+        let dir = std::env::temp_dir().join(format!("reimtris2-assets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("move.ogg"), b"override").unwrap();
+        let assets = Assets::with_dirs(vec![dir.clone()]);
+
+        assert_eq!(
+            assets.load("move.ogg").unwrap(),
+            Cow::Borrowed(&b"override"[..])
+        );
+        assert!(matches!(assets.load("music.ogg"), Ok(Cow::Borrowed(_))));
+
+        let err = assets.load("missing.ogg").unwrap_err();
+        assert!(err.contains(&dir.join("missing.ogg").display().to_string()));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,99 +1,713 @@
-use std::{fs::File, io::BufReader, sync::mpsc};
+use std::{
+    collections::HashMap,
+    io::Cursor,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
 
 use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
 
-use crate::game::SoundEffect;
+use super::assets::Assets;
+use super::sound_pack::{Event, Sound, SoundPack};
+use crate::board::Board;
+use crate::config::{Audio, AudioBackend, Music};
+use crate::game::{Game, SoundEffect};
+use crate::mode::Mode;
 
-fn source_from_path<P: AsRef<std::path::Path>>(path: P) -> Decoder<BufReader<File>> {
-    let file = BufReader::new(File::open(path).unwrap());
-    let source = Decoder::new(file).unwrap();
-    source
+fn source_from_sound(sound: Sound) -> Result<Decoder<Cursor<Sound>>, String> {
+    Decoder::new(Cursor::new(sound)).map_err(|err| err.to_string())
 }
 
-fn play_audio<P: AsRef<std::path::Path>>(
-    stream_handle: &OutputStreamHandle,
-    sink: &mut Option<Sink>,
-    path: P,
-    volume: f32,
-) {
-    let source = source_from_path(path);
-    *sink = Sink::try_new(&stream_handle).ok();
-    if let Some(sink) = sink {
-        sink.set_volume(volume);
-        sink.append(source);
+fn ogg_duration(data: &[u8]) -> Option<Duration> {
+    let header = data.windows(7).position(|window| window == b"\x01vorbis")?;
+    let rate = u32::from_le_bytes(data.get(header + 12..header + 16)?.try_into().ok()?);
+    let page = data.windows(4).rposition(|window| window == b"OggS")?;
+    let granule = u64::from_le_bytes(data.get(page + 6..page + 14)?.try_into().ok()?);
+    (rate > 0).then(|| Duration::from_secs_f64(granule as f64 / rate as f64))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Levels {
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Levels {
+    pub fn new(audio: &Audio) -> Self {
+        let level = |volume: u8, muted: bool| {
+            if muted {
+                0.0
+            } else {
+                audio.master_volume.min(100) as f32 / 100.0 * volume.min(100) as f32 / 100.0
+            }
+        };
+        Self {
+            music: level(audio.music_volume, audio.music_muted),
+            sfx: level(audio.sfx_volume, audio.sfx_muted),
+        }
     }
 }
 
+pub fn intensity(game: &Game) -> u8 {
+    let stack = match game.board.stack_height() {
+        height if height * 4 >= Board::HEIGHT * 3 => 2,
+        height if height * 2 >= Board::HEIGHT => 1,
+        _ => 0,
+    };
+    let level = (game.score.level / 10).min(2) as u8;
+    stack.max(level)
+}
+
 pub enum Command {
-    ToggleMuted,
+    SetLevels(Levels),
     PlayEffect(SoundEffect),
+    StartMusic(Mode),
+    SetIntensity(u8),
+    Duck(bool),
 }
 
-pub fn audio_thread() -> mpsc::Sender<Command> {
-    let (sender, receiver) = mpsc::channel::<Command>();
+const VOICES_PER_EFFECT: usize = 4;
+const DUCKED_GAIN: f32 = 0.3;
+const DUCK_RATE: f32 = 2.0;
 
-    let _ = std::thread::spawn(move || {
-        let (_stream, stream_handle) = OutputStream::try_default().unwrap();
-        let music_sink = Sink::try_new(&stream_handle).unwrap();
-        let mut hard_drop_sink = None;
-        let mut line_clear_sink = None;
-        let mut move_sink = None;
-        let mut rotation_sink = None;
-        let mut muted = false;
+struct Crossfade {
+    elapsed: Duration,
+    duration: Duration,
+}
+
+impl Crossfade {
+    fn new(duration: Duration) -> Self {
+        Self {
+            elapsed: Duration::ZERO,
+            duration,
+        }
+    }
 
-        music_sink.append(source_from_path("resources/music.ogg").repeat_infinite());
+    fn advance(&mut self, dt: Duration) {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+    }
 
-        loop {
-            let Ok(cmd) = receiver.recv() else {
-                break;
+    fn done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    fn volumes(&self) -> (f32, f32) {
+        let progress = match self.duration.is_zero() {
+            true => 1.0,
+            false => self.elapsed.as_secs_f32() / self.duration.as_secs_f32(),
+        };
+        (1.0 - progress, progress)
+    }
+}
+
+trait Output {
+    fn set_music_volume(&mut self, volume: f32);
+    fn set_music_speed(&mut self, speed: f32);
+    fn play_music(&mut self, track: Sound, looping: bool, fade: Duration) -> Result<(), String>;
+    fn music_remaining(&self) -> Option<Duration>;
+    fn update(&mut self, dt: Duration);
+    fn play_effect(&mut self, effect: &SoundEffect, volume: f32) -> Result<(), String>;
+}
+
+struct NullOutput;
+
+impl Output for NullOutput {
+    fn set_music_volume(&mut self, _volume: f32) {}
+
+    fn set_music_speed(&mut self, _speed: f32) {}
+
+    fn play_music(&mut self, _track: Sound, _looping: bool, _fade: Duration) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn music_remaining(&self) -> Option<Duration> {
+        None
+    }
+
+    fn update(&mut self, _dt: Duration) {}
+
+    fn play_effect(&mut self, _effect: &SoundEffect, _volume: f32) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+struct Voices {
+    sinks: Vec<Sink>,
+    started: Vec<u64>,
+    plays: u64,
+}
+
+impl Voices {
+    fn new(stream_handle: &OutputStreamHandle) -> Result<Self, String> {
+        let sinks = (0..VOICES_PER_EFFECT)
+            .map(|_| Sink::try_new(stream_handle).map_err(|err| err.to_string()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            sinks,
+            started: vec![0; VOICES_PER_EFFECT],
+            plays: 0,
+        })
+    }
+
+    fn next_voice(&self) -> usize {
+        self.sinks.iter().position(Sink::empty).unwrap_or_else(|| {
+            (0..self.sinks.len())
+                .min_by_key(|idx| self.started[*idx])
+                .unwrap_or(0)
+        })
+    }
+
+    fn play<S>(
+        &mut self,
+        stream_handle: &OutputStreamHandle,
+        source: S,
+        volume: f32,
+    ) -> Result<(), String>
+    where
+        S: Source + Send + 'static,
+        S::Item: rodio::Sample + Send,
+        f32: rodio::cpal::FromSample<S::Item>,
+    {
+        let idx = self.next_voice();
+        if !self.sinks[idx].empty() {
+            self.sinks[idx] = Sink::try_new(stream_handle).map_err(|err| err.to_string())?;
+        }
+        self.started[idx] = self.plays;
+        self.plays += 1;
+
+        let sink = &self.sinks[idx];
+        sink.set_volume(volume);
+        sink.append(source);
+        Ok(())
+    }
+}
+
+struct RodioOutput {
+    _stream: OutputStream,
+    stream_handle: OutputStreamHandle,
+    effects: SoundPack,
+    voices: HashMap<Event, Voices>,
+    music_sink: Option<Sink>,
+    music_duration: Option<Duration>,
+    fading_sink: Option<Sink>,
+    crossfade: Crossfade,
+    music_volume: f32,
+    music_speed: f32,
+}
+
+impl RodioOutput {
+    fn new(effects: SoundPack) -> Result<Self, String> {
+        let (stream, stream_handle) = OutputStream::try_default().map_err(|err| err.to_string())?;
+
+        Ok(Self {
+            _stream: stream,
+            stream_handle,
+            effects,
+            voices: HashMap::new(),
+            music_sink: None,
+            music_duration: None,
+            fading_sink: None,
+            crossfade: Crossfade::new(Duration::ZERO),
+            music_volume: 1.0,
+            music_speed: 1.0,
+        })
+    }
+
+    fn apply_music_volume(&self) {
+        let (fading, current) = self.crossfade.volumes();
+        for (sink, volume) in [(&self.fading_sink, fading), (&self.music_sink, current)] {
+            let Some(sink) = sink else {
+                continue;
             };
+            sink.set_volume(self.music_volume * volume);
+            if self.music_volume > 0.0 {
+                sink.play();
+            } else {
+                sink.pause();
+            }
+        }
+    }
+}
+
+impl Output for RodioOutput {
+    fn set_music_volume(&mut self, volume: f32) {
+        self.music_volume = volume;
+        self.apply_music_volume();
+    }
+
+    fn set_music_speed(&mut self, speed: f32) {
+        self.music_speed = speed;
+        if let Some(sink) = &self.music_sink {
+            sink.set_speed(speed);
+        }
+    }
+
+    fn play_music(&mut self, track: Sound, looping: bool, fade: Duration) -> Result<(), String> {
+        let duration = ogg_duration(&track).filter(|_| !looping);
+        let source = source_from_sound(track)?;
+        let sink = Sink::try_new(&self.stream_handle).map_err(|err| err.to_string())?;
+        sink.set_speed(self.music_speed);
+        if looping {
+            sink.append(source.repeat_infinite());
+        } else {
+            sink.append(source);
+        }
+
+        self.fading_sink = self.music_sink.replace(sink);
+        self.music_duration = duration;
+        self.crossfade = Crossfade::new(match self.fading_sink {
+            Some(_) => fade,
+            None => Duration::ZERO,
+        });
+        self.apply_music_volume();
+        Ok(())
+    }
+
+    fn music_remaining(&self) -> Option<Duration> {
+        let sink = self.music_sink.as_ref()?;
+        if sink.empty() {
+            return Some(Duration::ZERO);
+        }
+        let remaining = self.music_duration?.saturating_sub(sink.get_pos());
+        Some(remaining.div_f32(self.music_speed.max(0.01)))
+    }
+
+    fn update(&mut self, dt: Duration) {
+        if self.fading_sink.is_none() {
+            return;
+        }
+        self.crossfade.advance(dt);
+        if self.crossfade.done() {
+            self.fading_sink = None;
+        }
+        self.apply_music_volume();
+    }
+
+    fn play_effect(&mut self, effect: &SoundEffect, volume: f32) -> Result<(), String> {
+        let Some((source, pack_volume)) = self.effects.pick(effect) else {
+            return Ok(());
+        };
+        let voices = match self.voices.entry(Event::of(effect)) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(Voices::new(&self.stream_handle)?)
+            }
+        };
+        voices.play(&self.stream_handle, source, volume * pack_volume)
+    }
+}
+
+struct Player {
+    output: Box<dyn Output>,
+    assets: Assets,
+    music: Music,
+    levels: Levels,
+    tracks: Vec<String>,
+    track: usize,
+    track_elapsed: Duration,
+    ducked: bool,
+    duck_gain: f32,
+}
 
-            let effect = match cmd {
-                Command::ToggleMuted => {
-                    muted = !muted;
-                    if muted {
-                        music_sink.pause();
-                    } else {
-                        music_sink.play();
-                    }
-                    continue;
+impl Player {
+    fn new(mut output: Box<dyn Output>, assets: Assets, music: Music, levels: Levels) -> Self {
+        output.set_music_volume(levels.music);
+        Self {
+            output,
+            assets,
+            music,
+            levels,
+            tracks: Vec::new(),
+            track: 0,
+            track_elapsed: Duration::ZERO,
+            ducked: false,
+            duck_gain: 1.0,
+        }
+    }
+
+    fn crossfade(&self) -> Duration {
+        Duration::from_secs_f64(self.music.crossfade.max(0.0))
+    }
+
+    fn play_track(&mut self) -> Result<(), String> {
+        let Some(name) = self.tracks.get(self.track) else {
+            return Ok(());
+        };
+        let track = self
+            .assets
+            .load(name)
+            .map_err(|err| format!("{name}: {err}"))?;
+        self.output
+            .play_music(track, !self.music.playlist, self.crossfade())
+            .map_err(|err| format!("{name}: {err}"))?;
+        self.track_elapsed = Duration::ZERO;
+        Ok(())
+    }
+
+    fn play_from(&mut self, start: usize) -> Result<(), String> {
+        let mut errors = Vec::new();
+        for offset in 0..self.tracks.len() {
+            self.track = (start + offset) % self.tracks.len();
+            match self.play_track() {
+                Ok(()) => return Ok(()),
+                Err(err) => errors.push(err),
+            }
+        }
+        if errors.is_empty() {
+            return Ok(());
+        }
+        self.tracks.clear();
+        Err(format!(
+            "no playable music, stopping: {}",
+            errors.join(", ")
+        ))
+    }
+
+    fn handle(&mut self, cmd: Command) -> Result<(), String> {
+        let effect = match cmd {
+            Command::SetLevels(levels) => {
+                self.levels = levels;
+                self.output.set_music_volume(levels.music * self.duck_gain);
+                return Ok(());
+            }
+            Command::StartMusic(mode) => {
+                self.tracks = self
+                    .music
+                    .mode_tracks
+                    .get(&mode)
+                    .filter(|tracks| !tracks.is_empty())
+                    .unwrap_or(&self.music.tracks)
+                    .clone();
+                self.ducked = false;
+                self.output.set_music_speed(1.0);
+                return self.play_from(0);
+            }
+            Command::SetIntensity(intensity) => {
+                if self.music.dynamic {
+                    self.output.set_music_speed(1.0 + 0.05 * intensity as f32);
                 }
-                Command::PlayEffect(effect) => effect,
+                return Ok(());
+            }
+            Command::Duck(ducked) => {
+                self.ducked = ducked;
+                return Ok(());
+            }
+            Command::PlayEffect(effect) => effect,
+        };
+
+        if self.levels.sfx <= 0.0 {
+            return Ok(());
+        }
+
+        let base_volume = 0.5;
+        let volume = match effect {
+            SoundEffect::LineClear(lines_cleared) => {
+                base_volume + (lines_cleared as f32 - 1.0) * 0.5
+            }
+            _ => base_volume,
+        };
+        self.output.play_effect(&effect, volume * self.levels.sfx)
+    }
+
+    fn update(&mut self, dt: Duration) -> Result<(), String> {
+        let target = if self.ducked { DUCKED_GAIN } else { 1.0 };
+        if self.duck_gain != target {
+            let step = DUCK_RATE * dt.as_secs_f32();
+            self.duck_gain = match self.duck_gain < target {
+                true => (self.duck_gain + step).min(target),
+                false => (self.duck_gain - step).max(target),
             };
+            self.output
+                .set_music_volume(self.levels.music * self.duck_gain);
+        }
 
-            if muted {
-                continue;
+        self.output.update(dt);
+        self.track_elapsed += dt;
+        if !self.music.playlist || self.tracks.is_empty() {
+            return Ok(());
+        }
+        let fade = self.crossfade().min(self.track_elapsed);
+        if self
+            .output
+            .music_remaining()
+            .is_some_and(|remaining| remaining <= fade)
+        {
+            self.play_from(self.track + 1)?;
+        }
+        Ok(())
+    }
+}
+
+fn open_output(effects: SoundPack, backend: AudioBackend) -> Box<dyn Output> {
+    match backend {
+        AudioBackend::Null => Box::new(NullOutput),
+        AudioBackend::Auto => match RodioOutput::new(effects) {
+            Ok(output) => Box::new(output),
+            Err(err) => {
+                eprintln!("audio: no output available, continuing without sound: {err}");
+                Box::new(NullOutput)
             }
+        },
+    }
+}
+
+pub fn audio_thread(
+    assets: &Assets,
+    audio: &Audio,
+    notices: &mut Vec<String>,
+) -> Result<mpsc::Sender<Command>, String> {
+    let (sender, receiver) = mpsc::channel::<Command>();
+    let effects = match &audio.sound_pack {
+        Some(name) => SoundPack::load(assets, name, notices).unwrap_or_else(|err| {
+            notices.push(format!("sound pack: {err}, using built-in sounds"));
+            SoundPack::builtin(assets, notices)
+        }),
+        None => SoundPack::builtin(assets, notices),
+    };
+    let backend = audio.backend;
+    let levels = Levels::new(audio);
+    let assets = assets.clone();
+    let music = audio.music.clone();
+
+    let _ = std::thread::spawn(move || {
+        let mut player = Player::new(open_output(effects, backend), assets, music, levels);
+        let mut last = Instant::now();
 
-            let base_volume = 0.5;
-            match effect {
-                SoundEffect::HardDrop => play_audio(
-                    &stream_handle,
-                    &mut hard_drop_sink,
-                    "resources/hard_drop.ogg",
-                    base_volume,
-                ),
-                SoundEffect::LineClear(lines_cleared) => play_audio(
-                    &stream_handle,
-                    &mut line_clear_sink,
-                    "resources/line_clear.ogg",
-                    base_volume + (lines_cleared as f32 - 1.0) * 0.5,
-                ),
-                SoundEffect::Move => play_audio(
-                    &stream_handle,
-                    &mut move_sink,
-                    "resources/move.ogg",
-                    base_volume,
-                ),
-                SoundEffect::Rotation => play_audio(
-                    &stream_handle,
-                    &mut rotation_sink,
-                    "resources/rotation.ogg",
-                    base_volume,
-                ),
+        loop {
+            let result = match receiver.recv_timeout(Duration::from_millis(20)) {
+                Ok(cmd) => player.handle(cmd),
+                Err(mpsc::RecvTimeoutError::Timeout) => Ok(()),
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
             };
+            if let Err(err) = result {
+                eprintln!("audio: {err}");
+            }
+
+            let now = Instant::now();
+            if let Err(err) = player.update(now - last) {
+                eprintln!("audio: {err}");
+            }
+            last = now;
         }
     });
 
-    sender
+    Ok(sender)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        audio_thread, ogg_duration, Command, Crossfade, Levels, NullOutput, Output, Player, Voices,
+    };
+    use crate::config::{Audio, AudioBackend, Music};
+    use crate::game::SoundEffect;
+    use crate::gui::assets::Assets;
+    use crate::gui::sound_pack::{Sound, SoundPack};
+    use crate::mode::Mode;
+    use rodio::{source::Zero, Sink, Source};
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    struct FakeOutput {
+        plays: Rc<Cell<usize>>,
+        remaining: Rc<Cell<Option<Duration>>>,
+    }
+
+    impl Output for FakeOutput {
+        fn set_music_volume(&mut self, _volume: f32) {}
+
+        fn set_music_speed(&mut self, _speed: f32) {}
+
+        fn play_music(
+            &mut self,
+            _track: Sound,
+            _looping: bool,
+            _fade: Duration,
+        ) -> Result<(), String> {
+            self.plays.set(self.plays.get() + 1);
+            Ok(())
+        }
+
+        fn music_remaining(&self) -> Option<Duration> {
+            self.remaining.get()
+        }
+
+        fn update(&mut self, _dt: Duration) {}
+
+        fn play_effect(&mut self, _effect: &SoundEffect, _volume: f32) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    fn null_player(music: Music, levels: Levels) -> Player {
+        Player::new(
+            Box::new(NullOutput),
+            Assets::with_dirs(Vec::new()),
+            music,
+            levels,
+        )
+    }
+
+    #[test]
+    fn null_backend_plays_silently() {
+        let mut audio = Audio::default();
+        let mut player = null_player(Music::default(), Levels::new(&audio));
+        assert!(player
+            .handle(Command::PlayEffect(SoundEffect::Move))
+            .is_ok());
+        audio.toggle_muted();
+        assert!(player
+            .handle(Command::SetLevels(Levels::new(&audio)))
+            .is_ok());
+        assert_eq!(
+            player.levels,
+            Levels {
+                music: 0.0,
+                sfx: 0.0
+            }
+        );
+
+        audio.backend = AudioBackend::Null;
+        let sender = audio_thread(&Assets::with_dirs(Vec::new()), &audio, &mut Vec::new()).unwrap();
+        for effect in [SoundEffect::HardDrop, SoundEffect::LineClear(4)] {
+            assert!(sender.send(Command::PlayEffect(effect)).is_ok());
+        }
+        assert!(sender.send(Command::SetLevels(Levels::new(&audio))).is_ok());
+    }
+
+    #[test]
+    fn music_follows_mode_and_ducks_on_game_over() {
+        let mut music = Music::default();
+        music
+            .mode_tracks
+            .insert(Mode::Classic, vec!["classic.ogg".to_string()]);
+        let mut player = null_player(music, Levels::new(&Audio::default()));
+
+        assert!(player.handle(Command::StartMusic(Mode::Guideline)).is_ok());
+        assert_eq!(player.tracks, vec!["music.ogg"]);
+        let err = player
+            .handle(Command::StartMusic(Mode::Classic))
+            .unwrap_err();
+        assert!(err.contains("classic.ogg"));
+
+        player.handle(Command::Duck(true)).unwrap();
+        player.update(Duration::from_millis(100)).unwrap();
+        assert!(player.duck_gain < 1.0 && player.duck_gain > 0.3);
+        player.update(Duration::from_secs(1)).unwrap();
+        assert_eq!(player.duck_gain, 0.3);
+
+        let mut crossfade = Crossfade::new(Duration::from_secs(2));
+        crossfade.advance(Duration::from_secs(1));
+        assert_eq!(crossfade.volumes(), (0.5, 0.5));
+        crossfade.advance(Duration::from_secs(5));
+        assert!(crossfade.done());
+    }
+
+    #[test]
+    fn effects_play_from_memory() {
+        let dir = std::env::temp_dir().join(format!("reimtris2-effects-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let embedded = Assets::with_dirs(Vec::new());
+        for name in ["hard_drop.ogg", "line_clear.ogg", "rotation.ogg"] {
+            std::fs::write(dir.join(name), embedded.load(name).unwrap()).unwrap();
+        }
+        let rotation = embedded.load("rotation.ogg").unwrap();
+        std::fs::write(dir.join("move.ogg"), &rotation).unwrap();
+
+        let effects = SoundPack::builtin(&Assets::with_dirs(vec![dir.clone()]), &mut Vec::new());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // the dir is gone, so reading it again would fail or fall back to the embedded move.ogg
+        let count = |sound| super::source_from_sound(sound).unwrap().count();
+        let samples = count(rotation);
+        assert_ne!(samples, count(embedded.load("move.ogg").unwrap()));
+        for _ in 0..2 {
+            let (source, _) = effects.pick(&SoundEffect::Move).unwrap();
+            assert_eq!(source.count(), samples);
+        }
+    }
+
+    #[test]
+    fn voices_steal_the_oldest_playing_sink() {
+        let (sinks, queues): (Vec<Sink>, Vec<_>) = (0..4).map(|_| Sink::new_idle()).unzip();
+        let mut voices = Voices {
+            sinks,
+            started: vec![0; 4],
+            plays: 0,
+        };
+        let play = |voices: &mut Voices| {
+            let idx = voices.next_voice();
+            voices.sinks[idx] = Sink::new_idle().0;
+            voices.sinks[idx].append(Zero::<f32>::new(1, 44100));
+            voices.started[idx] = voices.plays;
+            voices.plays += 1;
+            idx
+        };
+        for idx in 0..4 {
+            assert_eq!(play(&mut voices), idx);
+        }
+        // voice 2 finishes early, the next steal must still pick voice 0
+        voices.sinks[2] = Sink::new_idle().0;
+        assert_eq!(play(&mut voices), 2);
+        assert_eq!(play(&mut voices), 0);
+        assert_eq!(play(&mut voices), 1);
+        assert_eq!(play(&mut voices), 3);
+        drop(queues);
+    }
+
+    #[test]
+    fn playlist_crossfades_early_and_skips_bad_tracks() {
+        let plays = Rc::new(Cell::new(0));
+        let remaining = Rc::new(Cell::new(None));
+        let output = FakeOutput {
+            plays: plays.clone(),
+            remaining: remaining.clone(),
+        };
+        let music = Music {
+            tracks: ["music.ogg", "missing.ogg", "music.ogg"]
+                .map(String::from)
+                .to_vec(),
+            playlist: true,
+            crossfade: 2.0,
+            ..Music::default()
+        };
+        let levels = Levels::new(&Audio::default());
+        let mut player = Player::new(
+            Box::new(output),
+            Assets::with_dirs(Vec::new()),
+            music,
+            levels,
+        );
+
+        player.handle(Command::StartMusic(Mode::Guideline)).unwrap();
+        assert_eq!((player.track, plays.get()), (0, 1));
+        remaining.set(Some(Duration::from_secs(5)));
+        player.update(Duration::from_secs(3)).unwrap();
+        assert_eq!((player.track, plays.get()), (0, 1));
+        remaining.set(Some(Duration::from_millis(1500)));
+        player.update(Duration::from_millis(20)).unwrap();
+        assert_eq!((player.track, plays.get()), (2, 2));
+
+        let music = Music {
+            tracks: vec!["missing.ogg".to_string()],
+            playlist: true,
+            ..Music::default()
+        };
+        let mut player = null_player(music, levels);
+        let err = player
+            .handle(Command::StartMusic(Mode::Guideline))
+            .unwrap_err();
+        assert!(err.contains("missing.ogg"));
+        assert!(player.tracks.is_empty());
+        assert!(player.update(Duration::from_millis(20)).is_ok());
+    }
+
+    #[test]
+    fn ogg_duration_matches_decoded_length() {
+        let sound = Assets::with_dirs(Vec::new()).load("move.ogg").unwrap();
+        let duration = ogg_duration(&sound).unwrap();
+        let source = super::source_from_sound(sound).unwrap();
+        let (rate, channels) = (source.sample_rate(), source.channels());
+        let decoded = source.count() as f64 / channels as f64 / rate as f64;
+        assert!((duration.as_secs_f64() - decoded).abs() < 0.05);
+    }
 }
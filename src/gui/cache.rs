@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+pub struct LruCache<K, V> {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<K, (V, u64)>,
+}
+
+impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            tick: 0,
+            entries: HashMap::with_capacity(capacity),
+        }
+    }
+
+    pub fn get_or_try_insert<E>(
+        &mut self,
+        key: K,
+        insert: impl FnOnce() -> Result<V, E>,
+    ) -> Result<&V, E> {
+        self.tick += 1;
+        if !self.entries.contains_key(&key) {
+            let value = insert()?;
+            if self.entries.len() >= self.capacity {
+                let oldest = self
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, (_, used))| *used)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    self.entries.remove(&oldest);
+                }
+            }
+            self.entries.insert(key.clone(), (value, self.tick));
+        }
+        let entry = self.entries.get_mut(&key).expect("inserted above");
+        entry.1 = self.tick;
+        Ok(&entry.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LruCache;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        let mut misses = 0;
+        let mut get = |cache: &mut LruCache<&str, usize>, key: &'static str| {
+            *cache
+                .get_or_try_insert(key, || {
+                    misses += 1;
+                    Ok::<_, ()>(key.len())
+                })
+                .unwrap()
+        };
+
+        assert_eq!(get(&mut cache, "a"), 1);
+        assert_eq!(get(&mut cache, "bb"), 2);
+        assert_eq!(get(&mut cache, "a"), 1);
+        assert_eq!(get(&mut cache, "ccc"), 3);
+        assert_eq!(get(&mut cache, "a"), 1);
+        assert_eq!(cache.entries.len(), 2);
+        assert_eq!(get(&mut cache, "bb"), 2);
+        assert_eq!(misses, 4);
+
+        assert!(cache.get_or_try_insert("d", || Err("nope")).is_err());
+        assert_eq!(cache.entries.len(), 2);
+    }
+}
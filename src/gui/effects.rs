@@ -0,0 +1,327 @@
+use rand::Rng;
+
+use super::theme::Theme;
+use super::ui::{Font, GameUiCtx};
+use crate::config::Effects;
+use crate::game::{Game, SoundEffect};
+use crate::tetromino::Tetromino;
+
+const FLASH: f32 = 0.12;
+const PARTICLE_LIFE: f32 = 0.8;
+const PARTICLES_PER_CELL: usize = 2;
+const GRAVITY: f32 = 30.0;
+const TEXT_LIFE: f32 = 1.2;
+const BANNER_LIFE: f32 = 1.5;
+const SHAKE: f32 = 0.35;
+
+pub struct Landing {
+    cells: Vec<(i32, i32)>,
+    rows: Vec<(i32, Vec<Option<Tetromino>>)>,
+    back_to_back: bool,
+}
+
+impl Landing {
+    pub fn of(game: &Game) -> Self {
+        let current = &game.current_tetromino;
+        let y = game.board.lowest_y(current);
+        let cells: Vec<_> = current
+            .tetromino
+            .pattern(&current.direction)
+            .into_iter()
+            .map(|(x, y_offset)| (x as i32 + current.x as i32, y_offset as i32 + y as i32))
+            .collect();
+
+        let mut rows = Vec::new();
+        for (_, y) in &cells {
+            let Some(row) = game.board.get(*y as usize).filter(|_| *y >= 0) else {
+                continue;
+            };
+            if rows.iter().any(|(other, _)| other == y) {
+                continue;
+            }
+            let mut row = row.to_vec();
+            for (x, _) in cells.iter().filter(|(_, other)| other == y) {
+                row[*x as usize] = Some(current.tetromino.clone());
+            }
+            if row.iter().all(Option::is_some) {
+                rows.push((*y, row));
+            }
+        }
+
+        Self {
+            cells,
+            rows,
+            back_to_back: game.score.back_to_back,
+        }
+    }
+}
+
+struct Particle {
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    tetromino: Tetromino,
+    age: f32,
+}
+
+pub struct EffectsLayer {
+    settings: Effects,
+    flashes: Vec<(Vec<(i32, i32)>, f32)>,
+    particles: Vec<Particle>,
+    texts: Vec<(String, f32)>,
+    banner: Option<(String, f32)>,
+    shake: f32,
+}
+
+impl EffectsLayer {
+    pub fn new(settings: &Effects) -> Self {
+        Self {
+            settings: settings.clone(),
+            flashes: Vec::new(),
+            particles: Vec::new(),
+            texts: Vec::new(),
+            banner: None,
+            shake: 0.0,
+        }
+    }
+
+    fn motion(&self) -> bool {
+        self.settings.enabled && !self.settings.reduced_motion
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(&self.settings);
+    }
+
+    pub fn observe(&mut self, landing: &Landing, game: &Game, sounds: &[SoundEffect]) {
+        if !self.settings.enabled {
+            return;
+        }
+        let locked = sounds.iter().any(|sound| {
+            matches!(
+                sound,
+                SoundEffect::Lock | SoundEffect::HardDrop | SoundEffect::LineClear(_)
+            )
+        });
+        if locked && self.motion() {
+            self.flashes.push((landing.cells.clone(), 0.0));
+        }
+        for sound in sounds {
+            match sound {
+                SoundEffect::LineClear(lines) => {
+                    if self.motion() && self.settings.particles {
+                        self.burst(landing);
+                    }
+                    if *lines == 4 {
+                        if self.motion() && self.settings.screen_shake {
+                            self.shake = SHAKE;
+                        }
+                        self.text("TETRIS".to_string());
+                    }
+                    let difficult = *lines == 4
+                        || sounds
+                            .iter()
+                            .any(|sound| matches!(sound, SoundEffect::TSpin));
+                    if difficult && landing.back_to_back {
+                        self.text("BACK-TO-BACK".to_string());
+                    }
+                }
+                SoundEffect::TSpin => self.text("T-SPIN".to_string()),
+                SoundEffect::PerfectClear => self.text("PERFECT CLEAR".to_string()),
+                SoundEffect::Combo(combo) => self.text(format!("COMBO x {combo}")),
+                SoundEffect::LevelUp => {
+                    self.banner = Some((format!("LEVEL {}", game.score.level), 0.0));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn text(&mut self, text: String) {
+        if self.settings.action_text {
+            self.texts.push((text, 0.0));
+        }
+    }
+
+    fn burst(&mut self, landing: &Landing) {
+        let mut rng = rand::rng();
+        for (y, row) in &landing.rows {
+            for (x, tetromino) in row.iter().enumerate() {
+                let Some(tetromino) = tetromino else {
+                    continue;
+                };
+                for _ in 0..PARTICLES_PER_CELL {
+                    self.particles.push(Particle {
+                        x: x as f32 + 0.5,
+                        y: *y as f32 + 0.5,
+                        vx: rng.random_range(-6.0..6.0),
+                        vy: rng.random_range(-12.0..-2.0),
+                        tetromino: tetromino.clone(),
+                        age: 0.0,
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for (_, age) in &mut self.flashes {
+            *age += dt;
+        }
+        self.flashes.retain(|(_, age)| *age < FLASH);
+
+        for particle in &mut self.particles {
+            particle.age += dt;
+            particle.vy += GRAVITY * dt;
+            particle.x += particle.vx * dt;
+            particle.y += particle.vy * dt;
+        }
+        self.particles
+            .retain(|particle| particle.age < PARTICLE_LIFE);
+
+        for (_, age) in &mut self.texts {
+            *age += dt;
+        }
+        self.texts.retain(|(_, age)| *age < TEXT_LIFE);
+
+        if let Some((_, age)) = &mut self.banner {
+            *age += dt;
+            if *age >= BANNER_LIFE {
+                self.banner = None;
+            }
+        }
+
+        self.shake = (self.shake - dt).max(0.0);
+    }
+
+    pub fn shake_offset(&self, tile: i32) -> (i32, i32) {
+        if self.shake <= 0.0 {
+            return (0, 0);
+        }
+        let amplitude = tile as f32 / 3.0 * self.shake / SHAKE;
+        let time = SHAKE - self.shake;
+        (
+            (amplitude * (time * 83.0).sin()).round() as i32,
+            (amplitude * (time * 61.0).cos()).round() as i32,
+        )
+    }
+
+    pub fn draw<Ctx: GameUiCtx<Err>, Err>(
+        &self,
+        ctx: &mut Ctx,
+        font: &Font,
+        theme: &Theme,
+    ) -> Result<(), Err> {
+        let layout = ctx.layout()?;
+        let tile = layout.tile;
+
+        for (cells, _) in &self.flashes {
+            for (x, y) in cells {
+                if *y < 0 {
+                    continue;
+                }
+                let (x, y) = ctx.board_tile_position(*x, *y)?;
+                ctx.fill_rect(x, y, tile, tile, &theme.flash)?;
+            }
+        }
+
+        let size = (tile / 4).max(1);
+        for particle in &self.particles {
+            let x = layout.board.x + (particle.x * tile as f32) as i32 - size / 2;
+            let y = layout.board.y + (particle.y * tile as f32) as i32 - size / 2;
+            let color = theme.piece(&particle.tetromino);
+            ctx.fill_rect(x, y, size, size, &color)?;
+        }
+
+        let font = ctx.font(font)?;
+        let center_x = layout.board.x + layout.board.width / 2;
+        let mut y = layout.board.y + layout.board.height * 2 / 5;
+        for (text, age) in self.texts.iter().rev() {
+            let (width, height) = ctx.text_size(&font, text)?;
+            let rise = match self.motion() {
+                true => (age / TEXT_LIFE * tile as f32 * 2.0) as i32,
+                false => 0,
+            };
+            let x = center_x - width / 2;
+            ctx.fill_text(&font, text, x, y - rise, width, height, &theme.text)?;
+            y -= height;
+        }
+
+        if let Some((text, _)) = &self.banner {
+            let (width, height) = ctx.text_size(&font, text)?;
+            let padding = layout.padding;
+            let x = center_x - width / 2;
+            let y = layout.board.y + layout.board.height / 5;
+            ctx.fill_rect(
+                x - padding,
+                y - padding,
+                width + padding * 2,
+                height + padding * 2,
+                &theme.panel,
+            )?;
+            ctx.outline_rect(
+                x - padding - 1,
+                y - padding - 1,
+                width + padding * 2 + 2,
+                height + padding * 2 + 2,
+                &theme.panel_border,
+            )?;
+            ctx.fill_text(&font, text, x, y, width, height, &theme.text)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EffectsLayer, Landing};
+    use crate::board::Board;
+    use crate::config::{Config, Effects};
+    use crate::game::{Game, SoundEffect};
+    use crate::tetromino::Tetromino;
+
+    fn tetris() -> Landing {
+        let row = vec![Some(Tetromino::I); Board::WIDTH];
+        Landing {
+            cells: (16..20).map(|y| (0, y)).collect(),
+            rows: (16..20).map(|y| (y, row.clone())).collect(),
+            back_to_back: true,
+        }
+    }
+
+    #[test]
+    fn tetris_spawns_effects_that_expire_and_respect_reduced_motion() {
+        let config = Config::default();
+        let game = Game::new(config.settings(config.mode));
+        let sounds = [SoundEffect::LineClear(4), SoundEffect::LevelUp];
+
+        let mut effects = EffectsLayer::new(&Effects::default());
+        effects.observe(&tetris(), &game, &sounds);
+        assert_eq!(effects.flashes.len(), 1);
+        assert_eq!(
+            effects.particles.len(),
+            4 * Board::WIDTH * super::PARTICLES_PER_CELL
+        );
+        assert_eq!(effects.texts.len(), 2);
+        assert!(effects.banner.is_some());
+        assert_ne!(effects.shake_offset(24), (0, 0));
+
+        effects.update(0.5);
+        assert!(effects.flashes.is_empty() && effects.shake_offset(24) == (0, 0));
+        effects.update(1.5);
+        assert!(effects.particles.is_empty() && effects.texts.is_empty());
+        assert!(effects.banner.is_none());
+
+        let reduced = Effects {
+            reduced_motion: true,
+            ..Effects::default()
+        };
+        let mut effects = EffectsLayer::new(&reduced);
+        effects.observe(&tetris(), &game, &sounds);
+        assert!(effects.flashes.is_empty() && effects.particles.is_empty());
+        assert_eq!(effects.shake_offset(24), (0, 0));
+        assert_eq!(effects.texts.len(), 2);
+    }
+}
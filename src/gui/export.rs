@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::assets::{Assets, FONT};
+use super::skin::Skin;
+use super::software::SoftwareCtx;
+use super::theme::Theme;
+use super::ui::{Font, GameUiCtx, Image};
+use crate::actions::ActionsHeld;
+use crate::config::Config;
+use crate::game::Game;
+use crate::replay::{Playback, Replay};
+
+fn screenshot_dir() -> Result<PathBuf, String> {
+    let pictures = std::env::var_os("XDG_PICTURES_DIR")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Pictures")))
+        .filter(|dir| dir.is_dir());
+    if let Some(pictures) = pictures {
+        return Ok(pictures.join("reimtris2"));
+    }
+    xdg::BaseDirectories::with_prefix("reimtris2")
+        .map_err(|err| err.to_string())?
+        .create_data_directory("screenshots")
+        .map_err(|err| err.to_string())
+}
+
+pub fn save_screenshot(image: &Image) -> Result<PathBuf, String> {
+    let dir = screenshot_dir()?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|err| format!("could not create '{}': {err}", dir.display()))?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis())
+        .unwrap_or_default();
+    let path = dir.join(format!("reimtris2-{millis}.png"));
+    image.save_png(&path)?;
+    Ok(path)
+}
+
+pub fn export_replay(
+    config: &Config,
+    replay: Replay,
+    dir: &Path,
+    (width, height): (u32, u32),
+    fps: u32,
+) -> Result<usize, String> {
+    render_frames(&Assets::new()?, config, replay, dir, (width, height), fps)
+}
+
+fn render_frames(
+    assets: &Assets,
+    config: &Config,
+    replay: Replay,
+    dir: &Path,
+    (width, height): (u32, u32),
+    fps: u32,
+) -> Result<usize, String> {
+    let font = Font::new(FONT, assets.load(FONT)?, 24);
+    let mut theme = Theme::load(assets, &config.theme)?;
+    if let Some(skin) = &config.skin {
+        theme.skin = Some(Skin::load(assets, skin)?);
+    }
+    std::fs::create_dir_all(dir)
+        .map_err(|err| format!("could not create '{}': {err}", dir.display()))?;
+
+    let mut game = Game::new(replay.settings());
+    let mut actions = ActionsHeld::new();
+    let mut playback = Playback::new(replay);
+    let mut ctx = SoftwareCtx::new(width, height);
+    let ticks_per_frame = config.timing.tick_rate / fps.max(1) as f64;
+    if ticks_per_frame.is_nan() || ticks_per_frame <= 0.0 {
+        return Err(format!(
+            "tick rate {} must be positive",
+            config.timing.tick_rate
+        ));
+    }
+
+    let mut frame = 0;
+    loop {
+        let tick = (frame as f64 * ticks_per_frame).round() as usize;
+        while game.ticks < tick && !playback.finished(&game) {
+            playback.apply(&game, &mut actions);
+            game.step(&actions);
+        }
+        ctx.draw_game(&font, &game, &theme)?;
+        ctx.save_png(dir.join(format!("frame_{frame:05}.png")))?;
+        frame += 1;
+        if playback.finished(&game) {
+            return Ok(frame);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::render_frames;
+    use crate::actions::Action;
+    use crate::config::Config;
+    use crate::game::Game;
+    use crate::gui::assets::Assets;
+    use crate::replay::Replay;
+
+    #[test]
+    fn exports_one_frame_per_step() {
+        let config = Config::default();
+        let settings = config.settings(config.mode);
+        let mut replay = Replay::new(&settings, &Game::new(settings.clone()));
+        replay.record(0, Action::HardDrop, true);
+        replay.record(1, Action::HardDrop, false);
+        replay.end_tick = 30;
+
+        let dir = std::env::temp_dir().join(format!("reimtris2-export-{}", std::process::id()));
+        let assets = Assets::with_dirs(Vec::new());
+        let frames = render_frames(&assets, &config, replay, &dir, (320, 240), 30);
+        let mut names: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let ticks_per_frame = config.timing.tick_rate / 30.0;
+        let expected = (30.0 / ticks_per_frame).ceil() as usize + 1;
+        assert_eq!(frames, Ok(expected));
+        names.sort();
+        let expected: Vec<String> = (0..expected)
+            .map(|frame| format!("frame_{frame:05}.png"))
+            .collect();
+        assert_eq!(names, expected);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::board::Board;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Area {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Area {
+    fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    pub tile: i32,
+    pub font_size: u16,
+    pub padding: i32,
+    pub board: Area,
+    pub hold: Area,
+    pub queue: Area,
+    pub stats: Area,
+    pub compact: bool,
+}
+
+impl Layout {
+    const MIN_TILE: f32 = 16.0;
+    const MIN_FONT: f32 = 10.0;
+
+    const COLUMNS: i32 = Board::WIDTH as i32 + 18;
+    const ROWS: i32 = Board::HEIGHT as i32 + 2;
+    const COMPACT_COLUMNS: i32 = Board::WIDTH as i32 + 7;
+    const COMPACT_ROWS: i32 = Board::HEIGHT as i32 + 3;
+
+    pub fn new(width: i32, height: i32, scale: f32) -> Self {
+        let tile = (width / Self::COLUMNS).min(height / Self::ROWS);
+        if (tile as f32) < Self::MIN_TILE * scale {
+            return Self::compact(width, height, scale);
+        }
+
+        let board = Self::board(tile, width, height);
+        let side = tile * 5;
+        Self {
+            tile,
+            font_size: Self::font_size(tile, scale),
+            padding: Self::padding(tile, scale),
+            board,
+            hold: Area::new(board.x - side - tile, board.y, side, tile * 4),
+            queue: Area::new(board.x - side - tile, board.y + tile * 5, side, tile * 12),
+            stats: Area::new(
+                board.x + board.width + tile,
+                board.y + tile,
+                tile * 8,
+                board.height - tile,
+            ),
+            compact: false,
+        }
+    }
+
+    fn compact(width: i32, height: i32, scale: f32) -> Self {
+        let tile = (width / Self::COMPACT_COLUMNS)
+            .min(height / Self::COMPACT_ROWS)
+            .max(2);
+        let side = tile * 5;
+        let total = Board::WIDTH as i32 * tile + tile + side;
+        let board = Area::new(
+            (width - total) / 2,
+            (height - tile * (Board::HEIGHT as i32 + 1)) / 2 + tile,
+            Board::WIDTH as i32 * tile,
+            Board::HEIGHT as i32 * tile,
+        );
+        let x = board.x + board.width + tile;
+        Self {
+            tile,
+            font_size: Self::font_size(tile, scale),
+            padding: Self::padding(tile, scale),
+            board,
+            hold: Area::new(x, board.y, side, tile * 4),
+            queue: Area::new(x, board.y + tile * 5, side, tile * 12),
+            stats: Area::new(board.x, board.y - tile, total, tile),
+            compact: true,
+        }
+    }
+
+    fn board(tile: i32, width: i32, height: i32) -> Area {
+        let board_width = Board::WIDTH as i32 * tile;
+        let board_height = Board::HEIGHT as i32 * tile;
+        Area::new(
+            (width - board_width) / 2,
+            (height - board_height) / 2,
+            board_width,
+            board_height,
+        )
+    }
+
+    fn font_size(tile: i32, scale: f32) -> u16 {
+        (tile * 2 / 3).max((Self::MIN_FONT * scale) as i32) as u16
+    }
+
+    fn padding(tile: i32, scale: f32) -> i32 {
+        (tile / 3).max((4.0 * scale) as i32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Area, Layout};
+
+    fn overlaps(a: &Area, b: &Area) -> bool {
+        a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+    }
+
+    #[test]
+    fn scales_with_window_and_falls_back_to_compact() {
+        for (width, height, scale) in [(1000, 800, 1.0), (3840, 2160, 2.0), (320, 240, 1.0)] {
+            let layout = Layout::new(width, height, scale);
+            let areas = [layout.board, layout.hold, layout.queue, layout.stats];
+            for (i, area) in areas.iter().enumerate() {
+                assert!(area.x >= 0 && area.y >= 0, "{width}x{height}: {area:?}");
+                assert!(area.x + area.width <= width && area.y + area.height <= height);
+                for other in &areas[i + 1..] {
+                    assert!(
+                        !overlaps(area, other),
+                        "{width}x{height}: {area:?} {other:?}"
+                    );
+                }
+            }
+        }
+
+        let small = Layout::new(1000, 800, 1.0);
+        let large = Layout::new(3840, 2160, 2.0);
+        assert!(!small.compact && !large.compact);
+        assert!(large.tile > small.tile * 2);
+        assert!(large.font_size > small.font_size);
+        assert!(Layout::new(320, 240, 1.0).compact);
+        assert!(Layout::new(1000, 800, 3.0).compact);
+    }
+}
@@ -0,0 +1,274 @@
+use std::f32::consts::FRAC_PI_2;
+
+use super::theme::Theme;
+use super::ui::{GameUiCtx, Rgb};
+use crate::game::{Game, Phase, SoundEffect};
+use crate::tetromino::{Direction, Tetromino};
+
+const SETTLE: f32 = 0.08;
+const TRAIL: f32 = 0.25;
+
+pub struct Piece {
+    tetromino: Tetromino,
+    direction: Direction,
+    x: i8,
+    y: i8,
+    landing_y: i8,
+}
+
+impl Piece {
+    pub fn of(game: &Game) -> Self {
+        let current = &game.current_tetromino;
+        Self {
+            tetromino: current.tetromino.clone(),
+            direction: current.direction,
+            x: current.x,
+            y: current.y,
+            landing_y: game.board.lowest_y(current),
+        }
+    }
+}
+
+pub struct Pose {
+    pub x: f32,
+    pub y: f32,
+    pub angle: f32,
+}
+
+struct Trail {
+    tetromino: Tetromino,
+    cells: Vec<(usize, usize)>,
+    x: i8,
+    from_y: i8,
+    to_y: i8,
+    age: f32,
+}
+
+pub struct PieceMotion {
+    enabled: bool,
+    trails_enabled: bool,
+    offset: (f32, f32),
+    angle: f32,
+    age: f32,
+    trails: Vec<Trail>,
+}
+
+fn quarter_turns(direction: Direction) -> i32 {
+    match direction {
+        Direction::Up => 0,
+        Direction::Right => 1,
+        Direction::Down => 2,
+        Direction::Left => 3,
+    }
+}
+
+fn ease_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    1.0 - (1.0 - t).powi(3)
+}
+
+impl PieceMotion {
+    pub fn new(enabled: bool, trails_enabled: bool) -> Self {
+        Self {
+            enabled,
+            trails_enabled,
+            offset: (0.0, 0.0),
+            angle: 0.0,
+            age: SETTLE,
+            trails: Vec::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.enabled, self.trails_enabled);
+    }
+
+    fn remaining(&self) -> f32 {
+        1.0 - ease_out(self.age / SETTLE)
+    }
+
+    pub fn observe(&mut self, before: &Piece, game: &Game, sounds: &[SoundEffect]) {
+        if !self.enabled {
+            return;
+        }
+        if self.trails_enabled
+            && sounds
+                .iter()
+                .any(|sound| matches!(sound, SoundEffect::HardDrop))
+            && before.landing_y > before.y
+        {
+            self.trails.push(Trail {
+                tetromino: before.tetromino.clone(),
+                cells: before.tetromino.pattern(&before.direction),
+                x: before.x,
+                from_y: before.y,
+                to_y: before.landing_y,
+                age: 0.0,
+            });
+        }
+
+        let replaced = sounds.iter().any(|sound| {
+            matches!(
+                sound,
+                SoundEffect::HardDrop
+                    | SoundEffect::Lock
+                    | SoundEffect::LineClear(_)
+                    | SoundEffect::Hold
+            )
+        });
+        if replaced {
+            self.offset = (0.0, 0.0);
+            self.angle = 0.0;
+            self.age = SETTLE;
+            return;
+        }
+
+        let after = &game.current_tetromino;
+        let turns =
+            (quarter_turns(after.direction) - quarter_turns(before.direction)).rem_euclid(4);
+        let dx = (before.x - after.x) as f32;
+        let dy = match turns {
+            0 => 0.0,
+            _ => (before.y - after.y) as f32,
+        };
+        if dx == 0.0 && turns == 0 {
+            return;
+        }
+        let remaining = self.remaining();
+        let angle = match turns {
+            1 => -FRAC_PI_2,
+            2 => -FRAC_PI_2 * 2.0,
+            3 => FRAC_PI_2,
+            _ => 0.0,
+        };
+        self.offset = (
+            self.offset.0 * remaining + dx,
+            self.offset.1 * remaining + dy,
+        );
+        self.angle = self.angle * remaining + angle;
+        self.age = 0.0;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.age = (self.age + dt).min(SETTLE);
+        for trail in &mut self.trails {
+            trail.age += dt;
+        }
+        self.trails.retain(|trail| trail.age < TRAIL);
+    }
+
+    pub fn pose(&self, game: &Game, soft_drop: bool, alpha: f32) -> Option<Pose> {
+        if !self.enabled {
+            return None;
+        }
+        let current = &game.current_tetromino;
+        let level = game.score.level;
+        let delay = match soft_drop {
+            true => game.mode.soft_drop_gravity(level),
+            false => game.mode.gravity(level),
+        }
+        .max(1);
+        let falling = matches!(game.phase, Phase::Falling)
+            && !game.game_over
+            && game.board.lowest_y(current) > current.y;
+        let fall = match falling {
+            true => (((game.ticks + delay - 1) % delay) as f32 + alpha) / delay as f32,
+            false => 0.0,
+        };
+        let remaining = self.remaining();
+        Some(Pose {
+            x: current.x as f32 + self.offset.0 * remaining,
+            y: current.y as f32 + fall.min(1.0) + self.offset.1 * remaining,
+            angle: self.angle * remaining,
+        })
+    }
+
+    pub fn draw<Ctx: GameUiCtx<Err>, Err>(&self, ctx: &mut Ctx, theme: &Theme) -> Result<(), Err> {
+        let layout = ctx.layout()?;
+        let tile = layout.tile;
+        for trail in &self.trails {
+            let progress = ease_out(trail.age / TRAIL);
+            let color = theme.piece(&trail.tetromino);
+            let color = Rgb(
+                mix(color.0, theme.board.0, progress),
+                mix(color.1, theme.board.1, progress),
+                mix(color.2, theme.board.2, progress),
+            );
+            let width = (tile / 2).max(1);
+            let mut columns: Vec<(usize, usize)> = Vec::new();
+            for (x, y) in &trail.cells {
+                match columns.iter_mut().find(|(column, _)| column == x) {
+                    Some((_, top)) => *top = (*top).min(*y),
+                    None => columns.push((*x, *y)),
+                }
+            }
+            for (x, top) in columns {
+                let from = (trail.from_y + top as i8).max(0) as f32;
+                let to = (trail.to_y + top as i8) as f32;
+                let from = from + (to - from) * progress;
+                if to <= from {
+                    continue;
+                }
+                let x = layout.board.x + (trail.x as i32 + x as i32) * tile + (tile - width) / 2;
+                let y = layout.board.y + (from * tile as f32) as i32;
+                let height = ((to - from) * tile as f32) as i32;
+                ctx.fill_rect(x, y, width, height, &color)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn mix(from: u8, to: u8, t: f32) -> u8 {
+    (from as f32 + (to as f32 - from as f32) * t).round() as u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Piece, PieceMotion, SETTLE};
+    use crate::actions::{Action, ActionsHeld};
+    use crate::config::Config;
+    use crate::game::Game;
+
+    #[test]
+    fn eases_shifts_and_interpolates_gravity() {
+        let config = Config::default();
+        let mut game = Game::new(config.settings(config.mode));
+        let mut actions = ActionsHeld::new();
+        let mut motion = PieceMotion::new(true, true);
+
+        let delay = game.mode.gravity(game.score.level) as f32;
+        let before = motion.pose(&game, false, 0.0).unwrap();
+        let halfway = motion.pose(&game, false, 0.5).unwrap();
+        assert!((halfway.y - before.y - 0.5 / delay).abs() < 1e-4);
+
+        actions.insert(Action::Left, game.ticks);
+        let piece = Piece::of(&game);
+        let sounds = game.step(&actions);
+        motion.observe(&piece, &game, &sounds);
+        let x = game.current_tetromino.x as f32;
+        assert_eq!(x + 1.0, piece.x as f32);
+        let pose = motion.pose(&game, false, 0.0).unwrap();
+        assert!((pose.x - (x + 1.0)).abs() < 1e-4);
+
+        motion.update(SETTLE / 2.0);
+        let pose = motion.pose(&game, false, 0.0).unwrap();
+        assert!(pose.x > x && pose.x < x + 1.0);
+        motion.update(SETTLE);
+        let pose = motion.pose(&game, false, 0.0).unwrap();
+        assert_eq!(pose.x, x);
+
+        actions.reset();
+        actions.insert(Action::HardDrop, game.ticks);
+        let piece = Piece::of(&game);
+        let sounds = game.step(&actions);
+        motion.observe(&piece, &game, &sounds);
+        assert_eq!(motion.trails.len(), 1);
+        motion.update(1.0);
+        assert!(motion.trails.is_empty());
+
+        assert!(PieceMotion::new(false, true)
+            .pose(&game, false, 0.5)
+            .is_none());
+    }
+}
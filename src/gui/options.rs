@@ -0,0 +1,147 @@
+use super::session::prompt;
+use crate::config::{Binding, Button, Config, Control, Key};
+
+pub struct OptionsMenu {
+    pub selected: usize,
+    pub listening: bool,
+}
+
+pub enum MenuEvent {
+    None,
+    Changed,
+    Closed,
+}
+
+impl OptionsMenu {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            listening: false,
+        }
+    }
+
+    pub fn selected_control(&self) -> Control {
+        Control::ALL[self.selected]
+    }
+
+    pub fn handle(&mut self, config: &mut Config, binding: Binding) -> MenuEvent {
+        let cancel = matches!(
+            binding,
+            Binding::Key(Key::Escape) | Binding::Button(Button::B)
+        );
+
+        if self.listening {
+            self.listening = false;
+            if cancel {
+                return MenuEvent::None;
+            }
+            let bindings = config.bindings_mut(self.selected_control());
+            match bindings.iter().position(|bound| *bound == binding) {
+                Some(index) => {
+                    bindings.remove(index);
+                }
+                None => bindings.push(binding),
+            }
+            return MenuEvent::Changed;
+        }
+
+        match binding {
+            _ if cancel => MenuEvent::Closed,
+            Binding::Key(Key::Up) | Binding::Button(Button::DPadUp) => {
+                self.selected = self
+                    .selected
+                    .checked_sub(1)
+                    .unwrap_or(Control::ALL.len() - 1);
+                MenuEvent::None
+            }
+            Binding::Key(Key::Down) | Binding::Button(Button::DPadDown) => {
+                self.selected = (self.selected + 1) % Control::ALL.len();
+                MenuEvent::None
+            }
+            Binding::Key(Key::Enter) | Binding::Button(Button::A) => {
+                self.listening = true;
+                MenuEvent::None
+            }
+            Binding::Key(Key::Backspace | Key::Delete) | Binding::Button(Button::X) => {
+                match config.bindings_mut(self.selected_control()).pop() {
+                    Some(_) => MenuEvent::Changed,
+                    None => MenuEvent::None,
+                }
+            }
+            _ => MenuEvent::None,
+        }
+    }
+
+    pub fn lines(
+        &self,
+        config: &Config,
+        name: impl Fn(&Binding) -> String,
+    ) -> (Vec<String>, usize) {
+        let mut lines = vec![
+            "options ^_^".to_string(),
+            "[up | down] select, [enter] add or remove, [backspace] remove last, [esc] back"
+                .to_string(),
+            String::new(),
+        ];
+        let selected = lines.len() + self.selected;
+
+        for control in Control::ALL {
+            let bindings = if self.listening && control == self.selected_control() {
+                "press a key or button to add or remove...".to_string()
+            } else {
+                prompt(config.bindings(control), &name)
+            };
+            lines.push(format!("{control}: {bindings}"));
+        }
+
+        let conflicts = config.conflicts();
+        if !conflicts.is_empty() {
+            lines.push(String::new());
+        }
+        for (binding, controls) in conflicts {
+            let controls = controls
+                .iter()
+                .map(|control| control.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            lines.push(format!(
+                "warning: {} is bound to {controls}",
+                name(binding).to_lowercase()
+            ));
+        }
+
+        (lines, selected)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MenuEvent, OptionsMenu};
+    use crate::config::{Binding, Config, Control, Key};
+
+    #[test]
+    fn adds_and_removes_bindings() {
+        let mut config = Config::default();
+        let mut menu = OptionsMenu::new();
+
+        menu.handle(&mut config, Binding::Key(Key::Down));
+        assert_eq!(menu.selected_control(), Control::Right);
+
+        menu.handle(&mut config, Binding::Key(Key::Enter));
+        assert!(menu.listening);
+        let event = menu.handle(&mut config, Binding::Key(Key::D));
+        assert!(matches!(event, MenuEvent::Changed));
+        assert_eq!(config.right.last(), Some(&Binding::Key(Key::D)));
+
+        menu.handle(&mut config, Binding::Key(Key::Enter));
+        menu.handle(&mut config, Binding::Key(Key::Right));
+        assert!(!config.right.contains(&Binding::Key(Key::Right)));
+        assert!(config.right.contains(&Binding::Key(Key::D)));
+
+        menu.handle(&mut config, Binding::Key(Key::Backspace));
+        assert!(!config.right.contains(&Binding::Key(Key::D)));
+
+        let event = menu.handle(&mut config, Binding::Key(Key::Escape));
+        assert!(matches!(event, MenuEvent::Closed));
+    }
+}
@@ -0,0 +1,42 @@
+use ab_glyph::{point, Font, PxScale, ScaleFont};
+
+pub fn text_size<F: Font>(font: &F, scale: PxScale, text: &str) -> (i32, i32) {
+    let font = font.as_scaled(scale);
+    let mut width = 0.0;
+    let mut previous = None;
+    for char in text.chars() {
+        let id = font.glyph_id(char);
+        if let Some(previous) = previous {
+            width += font.kern(previous, id);
+        }
+        width += font.h_advance(id);
+        previous = Some(id);
+    }
+    (width.ceil() as i32, font.height().ceil() as i32)
+}
+
+pub fn coverage<F: Font>(font: &F, scale: PxScale, text: &str) -> Vec<(i32, i32, f32)> {
+    let scaled = font.as_scaled(scale);
+    let mut caret = point(0.0, scaled.ascent());
+    let mut previous = None;
+    let mut pixels = Vec::new();
+    for char in text.chars() {
+        let id = scaled.glyph_id(char);
+        if let Some(previous) = previous {
+            caret.x += scaled.kern(previous, id);
+        }
+        let glyph = id.with_scale_and_position(scale, caret);
+        caret.x += scaled.h_advance(id);
+        previous = Some(id);
+        let Some(outline) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outline.px_bounds();
+        outline.draw(|glyph_x, glyph_y, coverage| {
+            let x = bounds.min.x.floor() as i32 + glyph_x as i32;
+            let y = bounds.min.y.floor() as i32 + glyph_y as i32;
+            pixels.push((x, y, coverage));
+        });
+    }
+    pixels
+}
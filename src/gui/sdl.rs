@@ -1,59 +1,97 @@
-use crate::actions::{Action, ActionsHeld};
-use crate::config::{Config, Key};
-use crate::game::Game;
+use crate::actions::Action;
+use crate::cli::Cli;
+use crate::config::{Binding, Button, Config, Key};
+use crate::game::SoundEffect;
+use crate::mode::Settings;
+use crate::replay::Replay;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
-use sdl2::pixels::Color;
+use sdl2::pixels::{Color, PixelFormatEnum};
 use sdl2::rect::Rect;
 use sdl2::render::{Texture, TextureCreator, WindowCanvas};
-use sdl2::ttf::Sdl2TtfContext;
-use std::time::Duration;
+use sdl2::rwops::RWops;
+use sdl2::surface::Surface;
+use sdl2::ttf::{Font as TtfFont, Sdl2TtfContext};
+use sdl2::video::WindowContext;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
+use super::assets::{Assets, FONT};
 use super::audio::{self};
-use super::ui::{GameUiCtx, Rgb, UiCtx};
+use super::cache::LruCache;
+use super::effects::{EffectsLayer, Landing};
+use super::export;
+use super::motion::{Piece, PieceMotion};
+use super::session::{prompt, Session};
+use super::skin::Skin;
+use super::theme::Theme;
+use super::timestep::{FixedTimestep, RateCounter};
+use super::ui::{Font, GameUiCtx, Image, Offset, Rgb, UiCtx};
+
+type FontKey = (&'static str, u16);
+type TextKey = (&'static str, u16, String, Rgb);
 
 struct SdlUiCtx<'a> {
     canvas: &'a mut WindowCanvas,
-    ttf: Sdl2TtfContext,
+    texture_creator: &'a TextureCreator<WindowContext>,
+    images: HashMap<usize, Texture<'a>>,
+    ttf: &'a Sdl2TtfContext,
+    sources: HashMap<&'static str, &'a [u8]>,
+    fonts: LruCache<FontKey, TtfFont<'a, 'a>>,
+    texts: LruCache<TextKey, Texture<'a>>,
 }
 
 impl SdlUiCtx<'_> {
+    const FONT_CACHE: usize = 8;
+    const TEXT_CACHE: usize = 256;
+
     fn present(&mut self) {
         self.canvas.present();
     }
 
-    fn font_texture<'font, 'a, P: AsRef<std::path::Path>, Text: AsRef<str>, C>(
-        &self,
-        font: P,
-        text: Text,
-        texture_creator: &'font TextureCreator<C>,
-    ) -> Result<Texture<'font>, String> {
-        let font = self.ttf.load_font(font, 24)?;
-        let game_over_text = font
-            .render(text.as_ref())
-            .solid(Color::RGB(255, 255, 255))
-            .map_err(|err| err.to_string())?;
-        let texture = texture_creator
-            .create_texture_from_surface(game_over_text)
-            .map_err(|err| err.to_string())?;
-
-        Ok(texture)
+    fn screenshot(&self) -> Result<Image, String> {
+        let (width, height) = self.canvas.output_size()?;
+        let rgba = self.canvas.read_pixels(None, PixelFormatEnum::RGBA32)?;
+        Ok(Image::new(width, height, rgba))
     }
 }
 
+fn ttf_font<'f, 't>(
+    fonts: &'f mut LruCache<FontKey, TtfFont<'t, 't>>,
+    ttf: &'t Sdl2TtfContext,
+    sources: &HashMap<&'static str, &'t [u8]>,
+    font: &Font,
+) -> Result<&'f TtfFont<'t, 't>, String> {
+    fonts.get_or_try_insert((font.name, font.size), || {
+        let data = sources
+            .get(font.name)
+            .ok_or_else(|| format!("font '{}' was not loaded", font.name))?;
+        ttf.load_font_from_rwops(RWops::from_bytes(data)?, font.size)
+    })
+}
+
 impl UiCtx<String> for SdlUiCtx<'_> {
     fn window_size(&self) -> Result<(i32, i32), String> {
-        let (width, height) = self.canvas.window().size();
+        let (width, height) = self.canvas.output_size()?;
         Ok((width as i32, height as i32))
     }
 
+    fn scale(&self) -> f32 {
+        let (width, _) = self.canvas.window().size();
+        match self.canvas.output_size() {
+            Ok((output, _)) if width > 0 => output as f32 / width as f32,
+            _ => 1.0,
+        }
+    }
+
     fn fill_rect(
         &mut self,
         x: i32,
         y: i32,
         width: i32,
         height: i32,
-        rgb: &super::ui::Rgb,
+        rgb: &Rgb,
     ) -> Result<(), String> {
         self.canvas.set_draw_color(Color::RGB(rgb.0, rgb.1, rgb.2));
         self.canvas
@@ -67,7 +105,7 @@ impl UiCtx<String> for SdlUiCtx<'_> {
         y: i32,
         width: i32,
         height: i32,
-        rgb: &super::ui::Rgb,
+        rgb: &Rgb,
     ) -> Result<(), String> {
         self.canvas.set_draw_color(Color::RGB(rgb.0, rgb.1, rgb.2));
         self.canvas
@@ -75,34 +113,74 @@ impl UiCtx<String> for SdlUiCtx<'_> {
         Ok(())
     }
 
-    fn text_size<P: AsRef<std::path::Path>, Text: AsRef<str>>(
+    fn text_size<Text: AsRef<str>>(
         &mut self,
-        font: P,
+        font: &Font,
         text: Text,
     ) -> Result<(i32, i32), String> {
-        let texture_creator = self.canvas.texture_creator();
-        let texture = self.font_texture(font, text, &texture_creator)?;
-        let query = texture.query();
-        Ok((query.width as i32, query.height as i32))
+        let (width, height) = ttf_font(&mut self.fonts, self.ttf, &self.sources, font)?
+            .size_of(text.as_ref())
+            .map_err(|err| err.to_string())?;
+        Ok((width as i32, height as i32))
     }
 
-    fn fill_text<P: AsRef<std::path::Path>, Text: AsRef<str>>(
+    fn fill_text<Text: AsRef<str>>(
         &mut self,
-        font: P,
+        font: &Font,
         text: Text,
         x: i32,
         y: i32,
         width: i32,
         height: i32,
+        rgb: &Rgb,
     ) -> Result<(), String> {
-        let texture_creator = self.canvas.texture_creator();
-        let texture = self.font_texture(font, text, &texture_creator)?;
+        let (fonts, ttf, sources) = (&mut self.fonts, self.ttf, &self.sources);
+        let texture_creator = self.texture_creator;
+        let key = (font.name, font.size, text.as_ref().to_string(), *rgb);
+        let texture = self.texts.get_or_try_insert(key, || {
+            let surface = ttf_font(fonts, ttf, sources, font)?
+                .render(text.as_ref())
+                .solid(Color::RGB(rgb.0, rgb.1, rgb.2))
+                .map_err(|err| err.to_string())?;
+            texture_creator
+                .create_texture_from_surface(surface)
+                .map_err(|err| err.to_string())
+        })?;
         self.canvas.copy(
-            &texture,
+            texture,
             None,
             Some(Rect::new(x, y, width as u32, height as u32)),
-        )?;
-        Ok(())
+        )
+    }
+
+    fn draw_image(
+        &mut self,
+        image: &Image,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> Result<(), String> {
+        if !self.images.contains_key(&image.id) {
+            let mut pixels = image.rgba.clone();
+            let surface = Surface::from_data(
+                &mut pixels,
+                image.width,
+                image.height,
+                image.width * 4,
+                PixelFormatEnum::RGBA32,
+            )?;
+            let texture = self
+                .texture_creator
+                .create_texture_from_surface(&surface)
+                .map_err(|err| err.to_string())?;
+            self.images.insert(image.id, texture);
+        }
+        self.canvas.copy(
+            &self.images[&image.id],
+            None,
+            Some(Rect::new(x, y, width as u32, height as u32)),
+        )
     }
 
     fn clear(&mut self, rgb: &Rgb) -> Result<(), String> {
@@ -112,154 +190,355 @@ impl UiCtx<String> for SdlUiCtx<'_> {
     }
 }
 
-pub fn start_game(config: Config) -> Result<(), String> {
-    let mut game = Game::new();
-    let mut actions = ActionsHeld::new();
-    let mut paused = false;
+#[derive(Default)]
+struct PadButtons(HashMap<u32, HashSet<Button>>);
 
-    const FONT: &'static str = "resources/josenfin_sans_regular.ttf";
+impl PadButtons {
+    fn update(&mut self, pad: u32, button: Button, pressed: bool) -> Option<(Binding, bool)> {
+        let held = self.0.entry(pad).or_default();
+        let changed = match pressed {
+            true => held.insert(button),
+            false => held.remove(&button),
+        };
+        changed.then_some((Binding::Button(button), pressed))
+    }
+
+    fn unplug(&mut self, pad: u32) -> Vec<(Binding, bool)> {
+        self.0
+            .remove(&pad)
+            .into_iter()
+            .flatten()
+            .map(|button| (Binding::Button(button), false))
+            .collect()
+    }
+}
+
+fn binding_name(binding: &Binding) -> String {
+    let Binding::Key(key) = binding else {
+        return binding.to_string();
+    };
+    match Keycode::from_scancode(key.to_sdl2_scancode()) {
+        Some(keycode) if !keycode.name().is_empty() => keycode.name(),
+        _ => key.to_string(),
+    }
+}
+
+fn volume_text(config: &Config) -> String {
+    let level = |volume: u8, muted: bool| match muted {
+        true => "muted".to_string(),
+        false => format!("{}%", volume.min(100)),
+    };
+    format!(
+        "volume: {} | music: {} | sfx: {}",
+        level(config.audio.master_volume, false),
+        level(config.audio.music_volume, config.audio.music_muted),
+        level(config.audio.sfx_volume, config.audio.sfx_muted)
+    )
+}
+
+pub fn start_game(
+    config: Config,
+    config_path: PathBuf,
+    notices: Vec<String>,
+    settings: Settings,
+    replay: Option<Replay>,
+    cli: Cli,
+) -> Result<(), String> {
+    let mut session = Session::new(settings, replay, cli.record.is_some());
+    let result = run_game(config, config_path, notices, &cli, &mut session);
+    result.and(session.save_recording(cli.record.as_deref()))
+}
 
-    let audio_thread = audio::audio_thread();
+fn run_game(
+    mut config: Config,
+    config_path: PathBuf,
+    mut notices: Vec<String>,
+    cli: &Cli,
+    session: &mut Session,
+) -> Result<(), String> {
+    let mut screenshot = false;
+    let mut effects = EffectsLayer::new(&config.effects);
+    let mut motion = PieceMotion::new(
+        config.timing.smooth_movement,
+        config.effects.enabled && !config.effects.reduced_motion,
+    );
+
+    let assets = Assets::new()?;
+    let font = Font::new(FONT, assets.load(FONT)?, 24);
+    let mut theme = Theme::load(&assets, &config.theme).unwrap_or_else(|err| {
+        notices.push(format!("theme: {err}, using the default theme"));
+        Theme::default()
+    });
+    if let Some(skin) = &config.skin {
+        match Skin::load(&assets, skin) {
+            Ok(skin) => theme.skin = Some(skin),
+            Err(err) => notices.push(format!("skin: {err}, using flat colours")),
+        }
+    }
+    let audio_thread = audio::audio_thread(&assets, &config.audio, &mut notices)?;
+    let _ = audio_thread.send(audio::Command::StartMusic(session.game.mode));
+    let mut music_intensity = 0;
+    let mut music_ducked = false;
 
     let sdl_context = sdl2::init()?;
     let ttf_context = sdl2::ttf::init().unwrap();
     let video_subsystem = sdl_context.video()?;
 
-    let window = video_subsystem
-        .window("reimtris2", 1000, 800)
-        .resizable()
-        .maximized()
-        .position_centered()
-        .build()
-        .unwrap();
+    let (width, height) = cli.size.unwrap_or((1000, 800));
+    let mut window = video_subsystem.window("reimtris2", width, height);
+    window.resizable().allow_highdpi().position_centered();
+    if cli.size.is_none() {
+        window.maximized();
+    }
+    if cli.fullscreen {
+        window.fullscreen_desktop();
+    }
+    let window = window.build().map_err(|err| err.to_string())?;
 
-    let mut canvas = window.into_canvas().build().unwrap();
+    let mut canvas = window.into_canvas();
+    if config.timing.vsync {
+        canvas = canvas.present_vsync();
+    }
+    let mut canvas = canvas.build().unwrap();
+    let texture_creator = canvas.texture_creator();
     let mut ctx = SdlUiCtx {
         canvas: &mut canvas,
-        ttf: ttf_context,
+        texture_creator: &texture_creator,
+        images: HashMap::new(),
+        ttf: &ttf_context,
+        sources: HashMap::from([(font.name, &*font.data)]),
+        fonts: LruCache::new(SdlUiCtx::FONT_CACHE),
+        texts: LruCache::new(SdlUiCtx::TEXT_CACHE),
     };
+    let controller_subsystem = sdl_context.game_controller()?;
+    let mut controllers = HashMap::new();
+    let mut pad_buttons = PadButtons::default();
     let mut event_pump = sdl_context.event_pump()?;
-    'running: loop {
-        ctx.clear(&Rgb(16, 16, 16))?;
+    let mut timestep =
+        FixedTimestep::new(config.timing.tick_rate, config.timing.max_steps_per_frame);
+    let mut frame_rate = RateCounter::new();
+    let mut tick_rate = RateCounter::new();
+    let mut notices_until = Instant::now() + Duration::from_secs(10);
+    let mut volume_until = Instant::now();
+    let mut save_volume_at: Option<Instant> = None;
+    let mut last_frame = Instant::now();
+    let result = 'running: loop {
+        let frame_start = Instant::now();
+        let dt = frame_start.duration_since(last_frame).as_secs_f32();
+        last_frame = frame_start;
         for event in event_pump.poll_iter() {
-            match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
+            let bindings = match event {
+                Event::Quit { .. } => break 'running Ok(()),
+                Event::KeyDown {
                     keycode: Some(Keycode::Escape),
                     ..
-                } => break 'running Ok(()),
-                Event::MouseMotion { .. } => {
-                    if config.reimtris1_feature_parity {
-                        break 'running Ok(());
-                    }
+                } if session.options.is_none() && !config.bound(&Binding::Key(Key::Escape)) => {
+                    break 'running Ok(());
+                }
+                Event::MouseMotion { .. } if config.reimtris1_feature_parity => {
+                    break 'running Ok(());
                 }
                 Event::KeyDown {
-                    keycode: Some(keycode),
+                    scancode: Some(scancode),
                     repeat: false,
                     ..
-                } => {
-                    let Some(key) = Key::from_sdl2_keycode(keycode) else {
-                        continue;
-                    };
-                    if config.pause.contains(&key) {
-                        paused = !paused;
-                    };
-                    if config.restart.contains(&key) && !paused && game.game_over {
-                        game = Game::new();
-                    }
-                    if config.toggle_mute.contains(&key) {
-                        audio_thread.send(audio::Command::ToggleMuted).unwrap();
-                    }
-                    if config.left.contains(&key) {
-                        actions.insert(Action::Left, game.ticks);
-                    }
-                    if config.right.contains(&key) {
-                        actions.insert(Action::Right, game.ticks);
-                    }
-                    if config.soft_drop.contains(&key) {
-                        actions.insert(Action::SoftDrop, game.ticks);
-                    }
-                    if config.hard_drop.contains(&key) {
-                        actions.insert(Action::HardDrop, game.ticks);
-                    }
-                    if config.rotate_cw.contains(&key) {
-                        actions.insert(Action::RotateCw, game.ticks);
-                    }
-                    if config.rotate_ccw.contains(&key) {
-                        actions.insert(Action::RotateCcw, game.ticks);
-                    }
-                    if config.swap.contains(&key) {
-                        actions.insert(Action::Swap, game.ticks);
-                    }
-                }
+                } => Key::from_sdl2_scancode(scancode)
+                    .map(|key| (Binding::Key(key), true))
+                    .into_iter()
+                    .collect(),
                 Event::KeyUp {
-                    keycode: Some(keycode),
+                    scancode: Some(scancode),
                     repeat: false,
                     ..
-                } => {
-                    let Some(key) = Key::from_sdl2_keycode(keycode) else {
-                        continue;
-                    };
-                    if config.left.contains(&key) {
-                        actions.remove(&Action::Left);
-                    }
-                    if config.right.contains(&key) {
-                        actions.remove(&Action::Right);
+                } => Key::from_sdl2_scancode(scancode)
+                    .map(|key| (Binding::Key(key), false))
+                    .into_iter()
+                    .collect(),
+                Event::ControllerDeviceAdded { which, .. } => {
+                    match controller_subsystem.open(which) {
+                        Ok(controller) => {
+                            controllers.insert(controller.instance_id(), controller);
+                        }
+                        Err(err) => eprintln!("could not open controller {which}: {err}"),
                     }
-                    if config.soft_drop.contains(&key) {
-                        actions.remove(&Action::SoftDrop);
-                    }
-                    if config.hard_drop.contains(&key) {
-                        actions.remove(&Action::HardDrop);
-                    }
-                    if config.rotate_cw.contains(&key) {
-                        actions.remove(&Action::RotateCw);
-                    }
-                    if config.rotate_ccw.contains(&key) {
-                        actions.remove(&Action::RotateCcw);
-                    }
-                    if config.swap.contains(&key) {
-                        actions.remove(&Action::Swap);
+                    continue;
+                }
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    controllers.remove(&which);
+                    pad_buttons.unplug(which)
+                }
+                Event::ControllerButtonDown { which, button, .. } => pad_buttons
+                    .update(which, Button::from_sdl2_button(button), true)
+                    .into_iter()
+                    .collect(),
+                Event::ControllerButtonUp { which, button, .. } => pad_buttons
+                    .update(which, Button::from_sdl2_button(button), false)
+                    .into_iter()
+                    .collect(),
+                Event::ControllerAxisMotion {
+                    which, axis, value, ..
+                } => Button::from_sdl2_axis(axis, value, config.gamepad_deadzone)
+                    .into_iter()
+                    .filter_map(|(button, pressed)| pad_buttons.update(which, button, pressed))
+                    .collect(),
+                _ => continue,
+            };
+
+            for (binding, pressed) in bindings {
+                let handled = session.handle(&mut config, binding, pressed, Instant::now());
+                if handled.config_changed {
+                    if let Err(err) = config.save(&config_path) {
+                        notices.push(format!("could not save config: {err}"));
+                        notices_until = Instant::now() + Duration::from_secs(10);
                     }
                 }
-                _ => {}
+                screenshot |= handled.screenshot;
+                if handled.volume_pressed {
+                    volume_until = Instant::now() + Duration::from_secs(2);
+                }
+                if handled.volume_changed {
+                    let levels = audio::Levels::new(&config.audio);
+                    let _ = audio_thread.send(audio::Command::SetLevels(levels));
+                    save_volume_at = Some(Instant::now() + Duration::from_secs(2));
+                }
+                if handled.restarted {
+                    effects.reset();
+                    motion.reset();
+                    let _ = audio_thread.send(audio::Command::StartMusic(session.game.mode));
+                    music_intensity = 0;
+                    music_ducked = false;
+                }
             }
         }
 
-        ctx.draw_board(&game.board, &game.current_tetromino)?;
-        ctx.draw_bag(&game.held_tetromino, &game.next_tetrominos)?;
-        ctx.draw_score(FONT, &game.score)?;
-
-        if paused {
-            let keys = config
-                .pause
-                .iter()
-                .map(|v| v.to_string().to_lowercase())
-                .collect::<Vec<_>>()
-                .join(" | ");
+        if session.countdown_beat(Instant::now()) {
+            let _ = audio_thread.send(audio::Command::PlayEffect(SoundEffect::Countdown));
+        }
+
+        let mut steps = 0;
+        if session.frozen() {
+            timestep.reset();
+        } else {
+            for _ in 0..timestep.advance() {
+                let landing = Landing::of(&session.game);
+                let piece = Piece::of(&session.game);
+                let Some(sounds) = session.step() else {
+                    break;
+                };
+                effects.observe(&landing, &session.game, &sounds);
+                motion.observe(&piece, &session.game, &sounds);
+                for sound in sounds {
+                    let _ = audio_thread.send(audio::Command::PlayEffect(sound));
+                }
+                steps += 1;
+                if session.game.game_over {
+                    break;
+                }
+            }
+        }
+        tick_rate.record(steps);
+
+        let game = &session.game;
+        let intensity = audio::intensity(game);
+        if intensity != music_intensity {
+            music_intensity = intensity;
+            let _ = audio_thread.send(audio::Command::SetIntensity(intensity));
+        }
+        if game.game_over && !music_ducked {
+            music_ducked = true;
+            let _ = audio_thread.send(audio::Command::Duck(true));
+        }
+
+        if save_volume_at.is_some_and(|at| Instant::now() >= at) {
+            save_volume_at = None;
+            if let Err(err) = config.save(&config_path) {
+                notices.push(format!("could not save config: {err}"));
+                notices_until = Instant::now() + Duration::from_secs(10);
+            }
+        }
+
+        if !session.paused && session.options.is_none() {
+            effects.update(dt);
+            motion.update(dt);
+        }
+        let (x, y) = effects.shake_offset(ctx.layout()?.tile);
+        let mut shaken = Offset {
+            ctx: &mut ctx,
+            x,
+            y,
+        };
+        let soft_drop = session.actions.contains_key(&Action::SoftDrop);
+        let pose = motion.pose(game, soft_drop, timestep.alpha());
+        shaken.draw_game_posed(&font, game, pose.as_ref(), &theme)?;
+        motion.draw(&mut shaken, &theme)?;
+        effects.draw(&mut shaken, &font, &theme)?;
+
+        if let Some(menu) = &session.options {
+            let (lines, selected) = menu.lines(&config, binding_name);
+            ctx.draw_menu(&font, &lines, selected, &theme)?;
+        } else if session.paused {
+            let keys = prompt(&config.pause, binding_name);
             let paused = format!("game paused o_o... press [{keys}] to unpause !!");
 
-            ctx.draw_important_text(FONT, paused)?;
+            ctx.draw_important_text(&font, paused, &theme)?;
+        } else if let Some((_, beat)) = session.countdown {
+            ctx.draw_important_text(&font, format!("{beat}..."), &theme)?;
         } else if game.game_over {
-            let keys = config
-                .restart
-                .iter()
-                .map(|v| v.to_string().to_lowercase())
-                .collect::<Vec<_>>()
-                .join(" | ");
-
+            let keys = prompt(&config.restart, binding_name);
             let game_over = format!("game over T_T... press [{keys}] 2 restart :D");
-            ctx.draw_important_text(FONT, game_over)?;
-        } else {
-            let effects = game.step(&actions);
-            effects.into_iter().for_each(|effect| {
-                audio_thread
-                    .send(audio::Command::PlayEffect(effect))
-                    .unwrap()
-            });
+            ctx.draw_important_text(&font, game_over, &theme)?;
+        }
+
+        if config.timing.debug_overlay {
+            let debug = format!(
+                "fps: {:.0} | ticks/s: {:.0} ({})",
+                frame_rate.rate(),
+                tick_rate.rate(),
+                config.timing.tick_rate
+            );
+            ctx.draw_debug_text(&font, debug, &theme)?;
+        }
+
+        if Instant::now() < volume_until {
+            ctx.draw_indicator(&font, volume_text(&config), &theme)?;
+        }
+
+        if Instant::now() >= notices_until {
+            notices.clear();
+        }
+        if !notices.is_empty() {
+            ctx.draw_notices(&font, &notices, &theme)?;
+        }
+
+        if screenshot {
+            screenshot = false;
+            let notice = match ctx
+                .screenshot()
+                .and_then(|image| export::save_screenshot(&image))
+            {
+                Ok(path) => format!("saved screenshot to {}", path.display()),
+                Err(err) => format!("could not save screenshot: {err}"),
+            };
+            notices.push(notice);
+            notices_until = notices_until.max(Instant::now() + Duration::from_secs(5));
         }
 
         ctx.present();
-        ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+        frame_rate.record(1);
+
+        if let Some(fps_cap) = config.timing.fps_cap {
+            let frame = Duration::from_secs_f64(1.0 / fps_cap as f64);
+            if let Some(remaining) = frame.checked_sub(frame_start.elapsed()) {
+                std::thread::sleep(remaining);
+            }
+        }
+    };
+
+    if save_volume_at.is_some() {
+        if let Err(err) = config.save(&config_path) {
+            eprintln!("could not save config: {err}");
+        }
     }
+
+    result
 }
@@ -0,0 +1,279 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::options::{MenuEvent, OptionsMenu};
+use crate::actions::{Action, ActionsHeld};
+use crate::config::{Binding, Config};
+use crate::game::{Game, SoundEffect};
+use crate::mode::Settings;
+use crate::replay::{Playback, Replay};
+
+const COUNTDOWN: Duration = Duration::from_secs(3);
+
+#[derive(Default)]
+pub struct Handled {
+    pub restarted: bool,
+    pub config_changed: bool,
+    pub screenshot: bool,
+    pub volume_pressed: bool,
+    pub volume_changed: bool,
+}
+
+pub struct Session {
+    pub game: Game,
+    pub actions: ActionsHeld,
+    pub paused: bool,
+    pub countdown: Option<(Instant, u64)>,
+    pub options: Option<OptionsMenu>,
+    playback: Option<Playback>,
+    recording: Option<Replay>,
+    settings: Settings,
+}
+
+pub fn prompt(bindings: &[Binding], name: impl Fn(&Binding) -> String) -> String {
+    bindings
+        .iter()
+        .map(|binding| name(binding).to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn change_volume(config: &mut Config, binding: &Binding) -> (bool, bool) {
+    let volume = |config: &Config| {
+        (
+            config.audio.master_volume,
+            config.audio.music_volume,
+            config.audio.sfx_volume,
+            config.audio.music_muted,
+            config.audio.sfx_muted,
+        )
+    };
+    let before = volume(config);
+    if config.toggle_mute.contains(binding) {
+        config.audio.toggle_muted();
+    }
+    if config.toggle_music.contains(binding) {
+        config.audio.music_muted = !config.audio.music_muted;
+    }
+    if config.toggle_sfx.contains(binding) {
+        config.audio.sfx_muted = !config.audio.sfx_muted;
+    }
+    if config.volume_up.contains(binding) {
+        config.audio.change_volume(10);
+    }
+    if config.volume_down.contains(binding) {
+        config.audio.change_volume(-10);
+    }
+    for (bindings, delta) in [
+        (&config.music_volume_up, 10),
+        (&config.music_volume_down, -10),
+    ] {
+        if bindings.contains(binding) {
+            config.audio.change_music_volume(delta);
+        }
+    }
+    for (bindings, delta) in [(&config.sfx_volume_up, 10), (&config.sfx_volume_down, -10)] {
+        if bindings.contains(binding) {
+            config.audio.change_sfx_volume(delta);
+        }
+    }
+    let pressed = [
+        &config.toggle_mute,
+        &config.toggle_music,
+        &config.toggle_sfx,
+        &config.volume_up,
+        &config.volume_down,
+        &config.music_volume_up,
+        &config.music_volume_down,
+        &config.sfx_volume_up,
+        &config.sfx_volume_down,
+    ]
+    .iter()
+    .any(|bindings| bindings.contains(binding));
+    (pressed, volume(config) != before)
+}
+
+impl Session {
+    pub fn new(settings: Settings, replay: Option<Replay>, record: bool) -> Self {
+        let game = Game::new(settings.clone());
+        let recording = record.then(|| Replay::new(&settings, &game));
+        Self {
+            game,
+            actions: ActionsHeld::new(),
+            paused: false,
+            countdown: None,
+            options: None,
+            playback: replay.map(Playback::new),
+            recording,
+            settings,
+        }
+    }
+
+    pub fn frozen(&self) -> bool {
+        self.paused || self.countdown.is_some() || self.game.game_over || self.options.is_some()
+    }
+
+    fn record(&mut self, action: Action, pressed: bool) {
+        if let Some(recording) = &mut self.recording {
+            recording.record(self.game.ticks, action, pressed);
+        }
+    }
+
+    pub fn handle(
+        &mut self,
+        config: &mut Config,
+        binding: Binding,
+        pressed: bool,
+        now: Instant,
+    ) -> Handled {
+        let mut handled = Handled::default();
+        if let Some(menu) = &mut self.options {
+            if pressed {
+                match menu.handle(config, binding) {
+                    MenuEvent::None => {}
+                    MenuEvent::Changed => handled.config_changed = true,
+                    MenuEvent::Closed => self.options = None,
+                }
+            }
+            return handled;
+        }
+        let playing_back = self.playback.is_some();
+        if !pressed {
+            if !playing_back {
+                for action in config.actions(&binding) {
+                    self.actions.remove(&action);
+                    self.record(action, false);
+                }
+            }
+            return handled;
+        }
+        if config.options.contains(&binding) {
+            self.options = Some(OptionsMenu::new());
+            if !playing_back {
+                let held: Vec<Action> = self.actions.keys().copied().collect();
+                for action in held {
+                    self.record(action, false);
+                }
+                self.actions.reset();
+            }
+            return handled;
+        }
+        handled.screenshot = config.screenshot.contains(&binding);
+        if config.pause.contains(&binding) && !self.game.game_over {
+            self.countdown =
+                (self.paused && config.resume_countdown).then_some((now + COUNTDOWN, 0));
+            self.paused = !self.paused;
+        }
+        (handled.volume_pressed, handled.volume_changed) = change_volume(config, &binding);
+        if playing_back {
+            return handled;
+        }
+
+        let restart = config.restart.contains(&binding) && !self.paused && self.game.game_over;
+        let quick_restart = config.quick_restart.contains(&binding);
+        if restart || quick_restart {
+            self.game
+                .reset(quick_restart && config.quick_restart_same_seed);
+            self.actions.reset();
+            self.paused = false;
+            self.countdown = None;
+            if let Some(recording) = &mut self.recording {
+                *recording = Replay::new(&self.settings, &self.game);
+            }
+            handled.restarted = true;
+            return handled;
+        }
+        for action in config.actions(&binding) {
+            self.actions.insert(action, self.game.ticks);
+            self.record(action, true);
+        }
+        handled
+    }
+
+    pub fn countdown_beat(&mut self, now: Instant) -> bool {
+        let Some((resume_at, beat)) = self.countdown else {
+            return false;
+        };
+        let remaining = resume_at.saturating_duration_since(now);
+        if remaining.is_zero() {
+            self.countdown = None;
+            return false;
+        }
+        let secs = remaining.as_secs() + 1;
+        self.countdown = Some((resume_at, secs));
+        secs != beat
+    }
+
+    pub fn step(&mut self) -> Option<Vec<SoundEffect>> {
+        if let Some(playback) = &mut self.playback {
+            if playback.finished(&self.game) {
+                return None;
+            }
+            playback.apply(&self.game, &mut self.actions);
+        }
+        Some(self.game.step(&self.actions))
+    }
+
+    pub fn save_recording(self, path: Option<&Path>) -> Result<(), String> {
+        match (path, self.recording) {
+            (Some(path), Some(mut recording)) => {
+                recording.end_tick = self.game.ticks;
+                recording.save(path)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Session;
+    use crate::actions::Action;
+    use crate::config::{Binding, Config, Key};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn dispatches_pause_countdown_restart_and_recording() {
+        let mut config = Config {
+            resume_countdown: true,
+            ..Config::default()
+        };
+        let settings = config.settings(config.mode);
+        let mut session = Session::new(settings, None, true);
+        let now = Instant::now();
+        let left = config.left[0].clone();
+        let pause = config.pause[0].clone();
+
+        session.handle(&mut config, left.clone(), true, now);
+        assert!(session.actions.contains_key(&Action::Left));
+        session.handle(&mut config, left.clone(), false, now);
+        assert!(session.actions.is_empty());
+
+        session.handle(&mut config, pause.clone(), true, now);
+        assert!(session.paused && session.frozen());
+        session.handle(&mut config, pause, true, now);
+        assert!(!session.paused && session.countdown.is_some());
+        assert!(session.countdown_beat(now));
+        assert!(!session.countdown_beat(now));
+        assert!(!session.countdown_beat(now + Duration::from_secs(4)));
+        assert!(!session.frozen());
+
+        session.handle(&mut config, Binding::Key(Key::O), true, now);
+        assert!(session.options.is_some());
+        session.handle(&mut config, Binding::Key(Key::Escape), true, now);
+        assert!(session.options.is_none());
+
+        let quick_restart = config.quick_restart[0].clone();
+        let handled = session.handle(&mut config, quick_restart, true, now);
+        assert!(handled.restarted);
+        assert_eq!(session.game.ticks, 0);
+
+        let path = std::env::temp_dir().join(format!("reimtris2-session-{}", std::process::id()));
+        session.handle(&mut config, left, true, now);
+        session.step();
+        session.save_recording(Some(&path)).unwrap();
+        let replay = crate::replay::Replay::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!((replay.end_tick, replay.events.len()), (1, 1));
+    }
+}
@@ -0,0 +1,114 @@
+use super::assets::Assets;
+use super::ui::Image;
+use crate::tetromino::Tetromino;
+
+const TILES: usize = 8;
+const GHOST: usize = 7;
+
+#[derive(Debug, Clone)]
+pub struct Skin {
+    tiles: Vec<Image>,
+}
+
+impl Skin {
+    pub fn load(assets: &Assets, name: &str) -> Result<Self, String> {
+        let path = format!("skins/{name}.png");
+        Self::from_png(&assets.load(&path)?).map_err(|err| format!("invalid skin '{path}': {err}"))
+    }
+
+    pub fn from_png(png: &[u8]) -> Result<Self, String> {
+        let mut decoder = png::Decoder::new(png);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(|err| err.to_string())?;
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader
+            .next_frame(&mut pixels)
+            .map_err(|err| err.to_string())?;
+
+        let channels = match info.color_type {
+            png::ColorType::Rgba => 4,
+            png::ColorType::Rgb => 3,
+            png::ColorType::GrayscaleAlpha => 2,
+            png::ColorType::Grayscale => 1,
+            png::ColorType::Indexed => return Err("unexpected indexed colour".to_string()),
+        };
+        let size = info.height as usize;
+        if info.width as usize != size * TILES {
+            return Err(format!(
+                "expected {TILES} square tiles in a row, got a {}x{} sheet",
+                info.width, info.height
+            ));
+        }
+
+        let rgba = |idx: usize| -> [u8; 4] {
+            let pixel = &pixels[idx * channels..(idx + 1) * channels];
+            match channels {
+                4 => [pixel[0], pixel[1], pixel[2], pixel[3]],
+                3 => [pixel[0], pixel[1], pixel[2], 255],
+                2 => [pixel[0], pixel[0], pixel[0], pixel[1]],
+                _ => [pixel[0], pixel[0], pixel[0], 255],
+            }
+        };
+
+        let tiles = (0..TILES)
+            .map(|tile| {
+                let mut data = Vec::with_capacity(size * size * 4);
+                for y in 0..size {
+                    for x in 0..size {
+                        data.extend(rgba(y * size * TILES + tile * size + x));
+                    }
+                }
+                Image::new(size as u32, size as u32, data)
+            })
+            .collect();
+        Ok(Self { tiles })
+    }
+
+    pub fn piece(&self, tetromino: &Tetromino) -> &Image {
+        let idx = match tetromino {
+            Tetromino::I => 0,
+            Tetromino::J => 1,
+            Tetromino::L => 2,
+            Tetromino::O => 3,
+            Tetromino::S => 4,
+            Tetromino::T => 5,
+            Tetromino::Z => 6,
+        };
+        &self.tiles[idx]
+    }
+
+    pub fn ghost(&self) -> &Image {
+        &self.tiles[GHOST]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Skin;
+    use crate::tetromino::Tetromino;
+
+    fn sheet(width: u32, height: u32) -> Vec<u8> {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        let data = (0..height)
+            .flat_map(|_| (0..width).flat_map(|x| [(x / height) as u8, 0, 0]))
+            .collect::<Vec<_>>();
+        writer.write_image_data(&data).unwrap();
+        writer.finish().unwrap();
+        png
+    }
+
+    #[test]
+    fn splits_sheet_into_tiles() {
+        let skin = Skin::from_png(&sheet(16, 2)).unwrap();
+        let o = skin.piece(&Tetromino::O);
+        assert_eq!((o.width, o.height), (2, 2));
+        assert_eq!(&o.rgba[..4], &[3, 0, 0, 255]);
+        assert_eq!(&skin.ghost().rgba[..4], &[7, 0, 0, 255]);
+
+        assert!(Skin::from_png(&sheet(18, 2)).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use ab_glyph::{Font as _, FontArc, PxScale};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::raster;
+use super::ui::{Font, Image, Rgb, UiCtx};
+
+pub struct SoftwareCtx {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+    fonts: HashMap<&'static str, FontArc>,
+}
+
+impl SoftwareCtx {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            rgba: vec![0; width as usize * height as usize * 4],
+            fonts: HashMap::new(),
+        }
+    }
+
+    fn font(&mut self, font: &Font) -> Result<(FontArc, PxScale), String> {
+        if !self.fonts.contains_key(font.name) {
+            let parsed = FontArc::try_from_vec(font.data.to_vec())
+                .map_err(|err| format!("{}: {err}", font.name))?;
+            self.fonts.insert(font.name, parsed);
+        }
+        let parsed = self.fonts[font.name].clone();
+        let scale = parsed
+            .pt_to_px_scale(font.size as f32)
+            .ok_or_else(|| format!("{}: font has no units per em", font.name))?;
+        Ok((parsed, scale))
+    }
+
+    fn blend(&mut self, x: i32, y: i32, rgb: &Rgb, alpha: f32) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 || alpha <= 0.0 {
+            return;
+        }
+        let idx = (y as usize * self.width as usize + x as usize) * 4;
+        let alpha = alpha.min(1.0);
+        for (channel, value) in [rgb.0, rgb.1, rgb.2].into_iter().enumerate() {
+            let dst = self.rgba[idx + channel] as f32;
+            self.rgba[idx + channel] = (dst + (value as f32 - dst) * alpha).round() as u8;
+        }
+        self.rgba[idx + 3] = 255;
+    }
+
+    pub fn image(&self) -> Image {
+        Image::new(self.width, self.height, self.rgba.clone())
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        self.image().save_png(path)
+    }
+}
+
+impl UiCtx<String> for SoftwareCtx {
+    fn window_size(&self) -> Result<(i32, i32), String> {
+        Ok((self.width as i32, self.height as i32))
+    }
+
+    fn scale(&self) -> f32 {
+        1.0
+    }
+
+    fn fill_rect(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        rgb: &Rgb,
+    ) -> Result<(), String> {
+        for y in y.max(0)..(y + height).min(self.height as i32) {
+            for x in x.max(0)..(x + width).min(self.width as i32) {
+                self.blend(x, y, rgb, 1.0);
+            }
+        }
+        Ok(())
+    }
+
+    fn outline_rect(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        rgb: &Rgb,
+    ) -> Result<(), String> {
+        self.fill_rect(x, y, width, 1, rgb)?;
+        self.fill_rect(x, y + height - 1, width, 1, rgb)?;
+        self.fill_rect(x, y, 1, height, rgb)?;
+        self.fill_rect(x + width - 1, y, 1, height, rgb)
+    }
+
+    fn text_size<Text: AsRef<str>>(
+        &mut self,
+        font: &Font,
+        text: Text,
+    ) -> Result<(i32, i32), String> {
+        let (font, scale) = self.font(font)?;
+        Ok(raster::text_size(&font, scale, text.as_ref()))
+    }
+
+    fn fill_text<Text: AsRef<str>>(
+        &mut self,
+        font: &Font,
+        text: Text,
+        x: i32,
+        y: i32,
+        _: i32,
+        _: i32,
+        rgb: &Rgb,
+    ) -> Result<(), String> {
+        let (font, scale) = self.font(font)?;
+        for (glyph_x, glyph_y, coverage) in raster::coverage(&font, scale, text.as_ref()) {
+            self.blend(x + glyph_x, y + glyph_y, rgb, coverage);
+        }
+        Ok(())
+    }
+
+    fn draw_image(
+        &mut self,
+        image: &Image,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> Result<(), String> {
+        if image.width == 0 || image.height == 0 {
+            return Ok(());
+        }
+        for dst_y in 0..height.max(0) {
+            for dst_x in 0..width.max(0) {
+                let src_x = dst_x as u32 * image.width / width as u32;
+                let src_y = dst_y as u32 * image.height / height as u32;
+                let idx = (src_y * image.width + src_x) as usize * 4;
+                let pixel = &image.rgba[idx..idx + 4];
+                let rgb = Rgb(pixel[0], pixel[1], pixel[2]);
+                self.blend(x + dst_x, y + dst_y, &rgb, pixel[3] as f32 / 255.0);
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, rgb: &Rgb) -> Result<(), String> {
+        for pixel in self.rgba.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[rgb.0, rgb.1, rgb.2, 255]);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SoftwareCtx;
+    use crate::actions::{Action, ActionsHeld};
+    use crate::config::Config;
+    use crate::game::Game;
+    use crate::gui::assets::{Assets, FONT};
+    use crate::gui::theme::Theme;
+    use crate::gui::ui::{Font, GameUiCtx};
+    use std::path::PathBuf;
+
+    fn step(game: &mut Game, actions: &ActionsHeld, ticks: usize) {
+        for _ in 0..ticks {
+            if !game.game_over {
+                game.step(actions);
+            }
+        }
+    }
+
+    fn play(game: &mut Game, drops: usize) {
+        let mut actions = ActionsHeld::new();
+        for drop in 0..drops {
+            actions.insert([Action::Left, Action::Right][drop % 2], game.ticks);
+            step(game, &actions, drop % 4 * 6 + 1);
+            actions.reset();
+            actions.insert(Action::HardDrop, game.ticks);
+            step(game, &actions, 1);
+            actions.reset();
+            step(game, &actions, 30);
+        }
+    }
+
+    fn decode(png: &[u8]) -> Vec<u8> {
+        let decoder = png::Decoder::new(png);
+        let mut reader = decoder.read_info().unwrap();
+        let mut rgba = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut rgba).unwrap();
+        rgba
+    }
+
+    fn snapshot(name: &str, game: &Game, overlay: Option<&str>) {
+        let assets = Assets::with_dirs(Vec::new());
+        let font = Font::new(FONT, assets.load(FONT).unwrap(), 24);
+        let theme = Theme::default();
+        let mut ctx = SoftwareCtx::new(640, 480);
+        ctx.draw_game(&font, game, &theme).unwrap();
+        if let Some(text) = overlay {
+            ctx.draw_important_text(&font, text, &theme).unwrap();
+        }
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/gui/snapshots")
+            .join(format!("{name}.png"));
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            ctx.save_png(&path).unwrap();
+            return;
+        }
+
+        let golden = decode(&std::fs::read(&path).unwrap());
+        let differing = golden
+            .chunks_exact(4)
+            .zip(ctx.rgba.chunks_exact(4))
+            .filter(|(golden, actual)| {
+                golden
+                    .iter()
+                    .zip(actual.iter())
+                    .any(|(golden, actual)| golden.abs_diff(*actual) > 16)
+            })
+            .count();
+        if golden.len() != ctx.rgba.len() || differing > ctx.rgba.len() / 4 / 1000 {
+            let actual = std::env::temp_dir().join(format!("reimtris2-{name}.png"));
+            ctx.save_png(&actual).unwrap();
+            panic!(
+                "{name}: {differing} pixels differ from {}, see {} \
+                 (rerun with UPDATE_SNAPSHOTS=1 to accept)",
+                path.display(),
+                actual.display()
+            );
+        }
+    }
+
+    #[test]
+    fn renders_golden_snapshots() {
+        let config = Config::default();
+        let mut settings = config.settings(config.mode);
+        settings.seed = Some(7);
+
+        let mut game = Game::new(settings);
+        snapshot("empty_board", &game, None);
+
+        play(&mut game, 10);
+        assert!(!game.game_over);
+        snapshot("mid_game", &game, None);
+        snapshot(
+            "paused",
+            &game,
+            Some("game paused o_o... press [p] to unpause !!"),
+        );
+
+        play(&mut game, 200);
+        assert!(game.game_over);
+        snapshot(
+            "game_over",
+            &game,
+            Some("game over T_T... press [enter] 2 restart :D"),
+        );
+    }
+}
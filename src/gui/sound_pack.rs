@@ -0,0 +1,253 @@
+use std::{borrow::Cow, collections::HashMap, io::Cursor};
+
+use rand::Rng;
+use rodio::source::{Buffered, Speed};
+use rodio::{Decoder, Source};
+use serde::Deserialize;
+
+use super::assets::Assets;
+use crate::game::SoundEffect;
+
+pub type Sound = Cow<'static, [u8]>;
+pub type Buffer = Buffered<Decoder<Cursor<Sound>>>;
+
+const MAX_PITCH_VARIATION: f32 = 0.9;
+
+fn decode(sound: Sound) -> Result<Buffer, String> {
+    let buffer = Decoder::new(Cursor::new(sound))
+        .map_err(|err| err.to_string())?
+        .buffered();
+    buffer.clone().for_each(drop);
+    Ok(buffer)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Event {
+    HardDrop,
+    Lock,
+    LineClear,
+    Move,
+    Rotation,
+    Hold,
+    LevelUp,
+    TSpin,
+    PerfectClear,
+    Combo,
+    GameOver,
+    Countdown,
+}
+
+impl Event {
+    pub fn of(effect: &SoundEffect) -> Self {
+        match effect {
+            SoundEffect::HardDrop => Event::HardDrop,
+            SoundEffect::Lock => Event::Lock,
+            SoundEffect::LineClear(_) => Event::LineClear,
+            SoundEffect::Move => Event::Move,
+            SoundEffect::Rotation => Event::Rotation,
+            SoundEffect::Hold => Event::Hold,
+            SoundEffect::LevelUp => Event::LevelUp,
+            SoundEffect::TSpin => Event::TSpin,
+            SoundEffect::PerfectClear => Event::PerfectClear,
+            SoundEffect::Combo(_) => Event::Combo,
+            SoundEffect::GameOver => Event::GameOver,
+            SoundEffect::Countdown => Event::Countdown,
+        }
+    }
+
+    fn builtin(self) -> Option<&'static str> {
+        match self {
+            Event::HardDrop | Event::Lock => Some("hard_drop.ogg"),
+            Event::LineClear => Some("line_clear.ogg"),
+            Event::Move => Some("move.ogg"),
+            Event::Rotation | Event::Hold => Some("rotation.ogg"),
+            _ => None,
+        }
+    }
+
+    const ALL: [Event; 12] = [
+        Event::HardDrop,
+        Event::Lock,
+        Event::LineClear,
+        Event::Move,
+        Event::Rotation,
+        Event::Hold,
+        Event::LevelUp,
+        Event::TSpin,
+        Event::PerfectClear,
+        Event::Combo,
+        Event::GameOver,
+        Event::Countdown,
+    ];
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    files: Vec<String>,
+    #[serde(default = "Entry::default_volume")]
+    volume: f32,
+    #[serde(default)]
+    volume_variation: f32,
+    #[serde(default)]
+    pitch_variation: f32,
+}
+
+impl Entry {
+    fn default_volume() -> f32 {
+        1.0
+    }
+}
+
+struct Bank {
+    sounds: Vec<Buffer>,
+    volume: f32,
+    volume_variation: f32,
+    pitch_variation: f32,
+}
+
+impl Bank {
+    fn pick(&self, effect: &SoundEffect) -> (Speed<Buffer>, f32) {
+        let mut rng = rand::rng();
+        let sound = match effect {
+            SoundEffect::Combo(step) => {
+                &self.sounds[step.saturating_sub(1).min(self.sounds.len() - 1)]
+            }
+            _ => &self.sounds[rng.random_range(0..self.sounds.len())],
+        };
+        let mut vary = |variation: f32| match variation > 0.0 {
+            true => 1.0 + rng.random_range(-variation..=variation),
+            false => 1.0,
+        };
+        let volume = self.volume * vary(self.volume_variation);
+        let speed = vary(self.pitch_variation);
+        (sound.clone().speed(speed), volume)
+    }
+}
+
+pub struct SoundPack {
+    banks: HashMap<Event, Bank>,
+}
+
+impl SoundPack {
+    pub fn builtin(assets: &Assets, notices: &mut Vec<String>) -> Self {
+        let embedded = Assets::with_dirs(Vec::new());
+        let mut loaded = HashMap::new();
+        let mut banks = HashMap::new();
+        for event in Event::ALL {
+            let Some(name) = event.builtin() else {
+                continue;
+            };
+            let sound = loaded.entry(name).or_insert_with(|| {
+                let load = |assets: &Assets| decode(assets.load(name)?);
+                load(assets)
+                    .or_else(|err| {
+                        let sound = load(&embedded);
+                        let fallback = match sound {
+                            Ok(_) => "using the built-in copy",
+                            Err(_) => "playing without it",
+                        };
+                        notices.push(format!("sound {name}: {err}, {fallback}"));
+                        sound
+                    })
+                    .ok()
+            });
+            let Some(sound) = sound.clone() else {
+                continue;
+            };
+            let bank = Bank {
+                sounds: vec![sound],
+                volume: 1.0,
+                volume_variation: 0.0,
+                pitch_variation: 0.0,
+            };
+            banks.insert(event, bank);
+        }
+        Self { banks }
+    }
+
+    pub fn load(assets: &Assets, name: &str, notices: &mut Vec<String>) -> Result<Self, String> {
+        let dir = format!("sounds/{name}");
+        let manifest = assets.load(&format!("{dir}/pack.toml"))?;
+        let manifest = std::str::from_utf8(&manifest).map_err(|err| err.to_string())?;
+        let manifest: HashMap<Event, Entry> = toml::from_str(manifest)
+            .map_err(|err| format!("invalid sound pack '{name}': {err}"))?;
+
+        let mut pack = Self::builtin(assets, notices);
+        for (event, entry) in manifest {
+            if entry.files.is_empty() {
+                continue;
+            }
+            let sounds = entry
+                .files
+                .iter()
+                .map(|file| {
+                    let path = format!("{dir}/{file}");
+                    decode(assets.load(&path)?).map_err(|err| format!("{path}: {err}"))
+                })
+                .collect::<Result<_, _>>();
+            let sounds = match sounds {
+                Ok(sounds) => sounds,
+                Err(err) => {
+                    notices.push(format!("sound pack: {err}, keeping the built-in sound"));
+                    continue;
+                }
+            };
+            let bank = Bank {
+                sounds,
+                volume: entry.volume,
+                volume_variation: entry.volume_variation.clamp(0.0, 1.0),
+                pitch_variation: entry.pitch_variation.clamp(0.0, MAX_PITCH_VARIATION),
+            };
+            pack.banks.insert(event, bank);
+        }
+        Ok(pack)
+    }
+
+    pub fn pick(&self, effect: &SoundEffect) -> Option<(Speed<Buffer>, f32)> {
+        Some(self.banks.get(&Event::of(effect))?.pick(effect))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Event, SoundPack};
+    use crate::game::SoundEffect;
+    use crate::gui::assets::Assets;
+
+    #[test]
+    fn packs_fall_back_to_builtin_and_clamp_variation() {
+        let dir = std::env::temp_dir().join(format!("reimtris2-pack-{}", std::process::id()));
+        let pack = dir.join("sounds/test");
+        std::fs::create_dir_all(&pack).unwrap();
+        let embedded = Assets::with_dirs(Vec::new());
+        std::fs::write(pack.join("a.ogg"), embedded.load("move.ogg").unwrap()).unwrap();
+        std::fs::write(pack.join("b.ogg"), embedded.load("rotation.ogg").unwrap()).unwrap();
+        std::fs::write(pack.join("broken.ogg"), "not vorbis").unwrap();
+        std::fs::write(dir.join("hard_drop.ogg"), "not vorbis").unwrap();
+        std::fs::write(
+            pack.join("pack.toml"),
+            "[combo]\nfiles = [\"a.ogg\", \"b.ogg\"]\n\n[move]\nfiles = [\"a.ogg\"]\npitch_variation = 4.0\n\n[rotation]\nfiles = [\"broken.ogg\"]\n",
+        )
+        .unwrap();
+
+        let mut notices = Vec::new();
+        let assets = Assets::with_dirs(vec![dir.clone()]);
+        let sounds = SoundPack::load(&assets, "test", &mut notices).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(notices.len(), 2, "{notices:?}");
+        assert!(notices[0].contains("hard_drop.ogg"));
+        assert!(notices[1].contains("broken.ogg"));
+        assert!(sounds.pick(&SoundEffect::HardDrop).is_some());
+        assert!(sounds.pick(&SoundEffect::Rotation).is_some());
+
+        assert_eq!(sounds.banks[&Event::Combo].sounds.len(), 2);
+        assert!(sounds.pick(&SoundEffect::Combo(2)).is_some());
+        assert!(sounds.pick(&SoundEffect::Combo(9)).is_some());
+        assert!(sounds.pick(&SoundEffect::GameOver).is_none());
+        assert_eq!(sounds.banks[&Event::Move].pitch_variation, 0.9);
+        assert!(sounds.pick(&SoundEffect::Move).is_some());
+    }
+}
@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+
+use super::assets::Assets;
+use super::skin::Skin;
+use super::ui::Rgb;
+use crate::tetromino::Tetromino;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pieces {
+    pub i: Rgb,
+    pub j: Rgb,
+    pub l: Rgb,
+    pub o: Rgb,
+    pub s: Rgb,
+    pub t: Rgb,
+    pub z: Rgb,
+}
+
+impl Default for Pieces {
+    fn default() -> Self {
+        Self {
+            i: Rgb(0, 255, 255),
+            j: Rgb(0, 0, 255),
+            l: Rgb(255, 128, 0),
+            o: Rgb(255, 255, 0),
+            s: Rgb(0, 255, 0),
+            t: Rgb(255, 0, 255),
+            z: Rgb(255, 0, 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GhostStyle {
+    Outline,
+    Filled,
+    Hidden,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    pub pieces: Pieces,
+    pub background: Rgb,
+    pub board: Rgb,
+    pub border: Rgb,
+    pub grid: Option<Rgb>,
+    pub ghost: GhostStyle,
+    pub ghost_color: Option<Rgb>,
+    pub flash: Rgb,
+    pub text: Rgb,
+    pub panel: Rgb,
+    pub panel_border: Rgb,
+    pub glyphs: bool,
+    #[serde(skip)]
+    pub skin: Option<Skin>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            pieces: Pieces::default(),
+            background: Rgb(16, 16, 16),
+            board: Rgb(0, 0, 0),
+            border: Rgb(255, 255, 255),
+            grid: None,
+            ghost: GhostStyle::Outline,
+            ghost_color: Some(Rgb(255, 255, 255)),
+            flash: Rgb(255, 255, 255),
+            text: Rgb(255, 255, 255),
+            panel: Rgb(16, 16, 16),
+            panel_border: Rgb(255, 255, 255),
+            glyphs: false,
+            skin: None,
+        }
+    }
+}
+
+impl Theme {
+    pub const BUILTIN: [&'static str; 5] = [
+        "default",
+        "deuteranopia",
+        "protanopia",
+        "tritanopia",
+        "high_contrast",
+    ];
+
+    fn builtin(name: &str) -> Option<Self> {
+        let pieces = match name {
+            "default" => return Some(Self::default()),
+            "deuteranopia" => Pieces {
+                i: Rgb(86, 180, 233),
+                j: Rgb(0, 114, 178),
+                l: Rgb(230, 159, 0),
+                o: Rgb(240, 228, 66),
+                s: Rgb(0, 158, 115),
+                t: Rgb(204, 121, 167),
+                z: Rgb(213, 94, 0),
+            },
+            "protanopia" => Pieces {
+                i: Rgb(86, 180, 233),
+                j: Rgb(0, 90, 181),
+                l: Rgb(230, 159, 0),
+                o: Rgb(255, 255, 150),
+                s: Rgb(0, 158, 115),
+                t: Rgb(220, 150, 255),
+                z: Rgb(255, 120, 40),
+            },
+            "tritanopia" => Pieces {
+                i: Rgb(0, 190, 190),
+                j: Rgb(50, 110, 160),
+                l: Rgb(255, 140, 160),
+                o: Rgb(240, 240, 240),
+                s: Rgb(0, 140, 90),
+                t: Rgb(160, 60, 100),
+                z: Rgb(220, 30, 30),
+            },
+            "high_contrast" => {
+                return Some(Self {
+                    pieces: Pieces {
+                        i: Rgb(0, 255, 255),
+                        j: Rgb(110, 160, 255),
+                        l: Rgb(255, 160, 0),
+                        o: Rgb(255, 255, 0),
+                        s: Rgb(0, 255, 0),
+                        t: Rgb(255, 110, 255),
+                        z: Rgb(255, 80, 80),
+                    },
+                    background: Rgb(0, 0, 0),
+                    grid: Some(Rgb(64, 64, 64)),
+                    panel: Rgb(0, 0, 0),
+                    glyphs: true,
+                    ..Self::default()
+                })
+            }
+            _ => return None,
+        };
+        Some(Self {
+            pieces,
+            glyphs: true,
+            ..Self::default()
+        })
+    }
+
+    pub fn load(assets: &Assets, name: &str) -> Result<Self, String> {
+        if let Some(theme) = Self::builtin(name) {
+            return Ok(theme);
+        }
+        let path = format!("themes/{name}.toml");
+        let theme = assets.load(&path).map_err(|err| {
+            let builtin = Self::BUILTIN.join(", ");
+            format!("unknown theme '{name}' (built-in themes: {builtin}): {err}")
+        })?;
+        let theme = std::str::from_utf8(&theme).map_err(|err| format!("{path}: {err}"))?;
+        toml::from_str(theme).map_err(|err| format!("invalid theme '{path}': {err}"))
+    }
+
+    pub fn piece(&self, tetromino: &Tetromino) -> Rgb {
+        match tetromino {
+            Tetromino::I => self.pieces.i,
+            Tetromino::J => self.pieces.j,
+            Tetromino::L => self.pieces.l,
+            Tetromino::O => self.pieces.o,
+            Tetromino::S => self.pieces.s,
+            Tetromino::T => self.pieces.t,
+            Tetromino::Z => self.pieces.z,
+        }
+    }
+
+    pub fn glyph(tetromino: &Tetromino) -> &'static [(i32, i32, i32, i32)] {
+        match tetromino {
+            Tetromino::I => &[(1, 3, 6, 2)],
+            Tetromino::J => &[(3, 1, 2, 6)],
+            Tetromino::L => &[(2, 2, 4, 1), (2, 5, 4, 1), (2, 2, 1, 4), (5, 2, 1, 4)],
+            Tetromino::O => &[(3, 3, 2, 2)],
+            Tetromino::S => &[(5, 1, 2, 2), (1, 5, 2, 2)],
+            Tetromino::T => &[(1, 3, 6, 2), (3, 1, 2, 6)],
+            Tetromino::Z => &[(1, 1, 2, 2), (5, 5, 2, 2)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GhostStyle, Theme};
+    use crate::gui::assets::Assets;
+    use crate::gui::ui::Rgb;
+    use crate::tetromino::Tetromino;
+
+    #[test]
+    fn loads_builtin_and_partial_theme_files() {
+        let assets = Assets::with_dirs(Vec::new());
+        for name in Theme::BUILTIN {
+            assert!(Theme::load(&assets, name).is_ok());
+        }
+        assert!(Theme::load(&assets, "deuteranopia").unwrap().glyphs);
+        assert!(Theme::load(&assets, "nope")
+            .unwrap_err()
+            .contains("high_contrast"));
+
+        let theme: Theme = toml::from_str("background = [1, 2, 3]\nghost = \"hidden\"").unwrap();
+        assert_eq!(theme.background, Rgb(1, 2, 3));
+        assert_eq!(theme.ghost, GhostStyle::Hidden);
+        assert_eq!(theme.piece(&Tetromino::I), Rgb(0, 255, 255));
+
+        let theme: Theme = toml::from_str("[pieces]\nj = [1, 2, 3]").unwrap();
+        assert_eq!(theme.piece(&Tetromino::J), Rgb(1, 2, 3));
+        assert_eq!(theme.piece(&Tetromino::Z), Rgb(255, 0, 0));
+        let high_contrast = Theme::load(&assets, "high_contrast").unwrap();
+        assert_ne!(high_contrast.piece(&Tetromino::J), Rgb(0, 0, 255));
+    }
+}
@@ -0,0 +1,108 @@
+use std::time::{Duration, Instant};
+
+pub struct FixedTimestep {
+    tick: Duration,
+    max_steps: usize,
+    accumulator: Duration,
+    last: Instant,
+}
+
+impl FixedTimestep {
+    pub fn new(tick_rate: f64, max_steps: usize) -> Self {
+        Self {
+            tick: Duration::from_secs_f64(1.0 / tick_rate),
+            max_steps: max_steps.max(1),
+            accumulator: Duration::ZERO,
+            last: Instant::now(),
+        }
+    }
+
+    pub fn advance(&mut self) -> usize {
+        let now = Instant::now();
+        self.advance_by(now - self.last);
+        self.last = now;
+        self.take_steps()
+    }
+
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator.as_secs_f32() / self.tick.as_secs_f32()).min(1.0)
+    }
+
+    pub fn reset(&mut self) {
+        self.accumulator = Duration::ZERO;
+        self.last = Instant::now();
+    }
+
+    fn advance_by(&mut self, elapsed: Duration) {
+        self.accumulator += elapsed;
+    }
+
+    fn take_steps(&mut self) -> usize {
+        let mut steps = 0;
+        while self.accumulator >= self.tick {
+            if steps >= self.max_steps {
+                self.accumulator = Duration::ZERO;
+                break;
+            }
+            self.accumulator -= self.tick;
+            steps += 1;
+        }
+        steps
+    }
+}
+
+pub struct RateCounter {
+    window_start: Instant,
+    count: usize,
+    rate: f64,
+}
+
+impl RateCounter {
+    pub fn new() -> Self {
+        Self {
+            window_start: Instant::now(),
+            count: 0,
+            rate: 0.0,
+        }
+    }
+
+    pub fn record(&mut self, count: usize) {
+        self.count += count;
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            self.rate = self.count as f64 / elapsed.as_secs_f64();
+            self.count = 0;
+            self.window_start = Instant::now();
+        }
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FixedTimestep;
+    use std::time::Duration;
+
+    #[test]
+    fn steps_at_fixed_rate_and_drops_backlog() {
+        let mut timestep = FixedTimestep::new(60.0, 5);
+        let tick = Duration::from_secs_f64(1.0 / 60.0);
+
+        timestep.advance_by(tick * 2);
+        assert_eq!(timestep.take_steps(), 2);
+
+        timestep.advance_by(tick / 2);
+        assert_eq!(timestep.take_steps(), 0);
+        assert!((timestep.alpha() - 0.5).abs() < 1e-3);
+        timestep.advance_by(tick);
+        assert_eq!(timestep.take_steps(), 1);
+
+        timestep.advance_by(tick * 60);
+        assert_eq!(timestep.take_steps(), 5);
+        timestep.advance_by(tick / 2);
+        assert_eq!(timestep.take_steps(), 0);
+    }
+}
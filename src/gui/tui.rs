@@ -0,0 +1,505 @@
+use crossterm::event::{
+    self, Event as TermEvent, KeyCode, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
+    PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use crossterm::{cursor, queue, style, terminal};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use super::assets::Assets;
+use super::session::{prompt, Session};
+use super::theme::Theme;
+use super::timestep::FixedTimestep;
+use super::ui::{Font, GameUiCtx, Image, Rgb, UiCtx};
+use crate::cli::Cli;
+use crate::config::{Binding, Config, Key};
+use crate::mode::Settings;
+use crate::replay::Replay;
+
+const ROW: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cell {
+    char: char,
+    fg: Rgb,
+    bg: Rgb,
+}
+
+struct TuiCtx {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+    drawn: Vec<Cell>,
+}
+
+impl TuiCtx {
+    const BLANK: Cell = Cell {
+        char: ' ',
+        fg: Rgb(255, 255, 255),
+        bg: Rgb(0, 0, 0),
+    };
+
+    fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Self::BLANK; width as usize * height as usize],
+            drawn: Vec::new(),
+        }
+    }
+
+    fn cell(&mut self, x: i32, row: i32) -> Option<&mut Cell> {
+        if x < 0 || row < 0 || x >= self.width as i32 || row >= self.height as i32 {
+            return None;
+        }
+        self.cells
+            .get_mut(row as usize * self.width as usize + x as usize)
+    }
+
+    fn present(&mut self, out: &mut impl Write) -> std::io::Result<()> {
+        let mut colors = None;
+        for (i, cell) in self.cells.iter().enumerate() {
+            if self.drawn.get(i) == Some(cell) {
+                continue;
+            }
+            let (x, y) = (i % self.width as usize, i / self.width as usize);
+            queue!(out, cursor::MoveTo(x as u16, y as u16))?;
+            if colors != Some((cell.fg, cell.bg)) {
+                let (fg, bg) = (color(&cell.fg), color(&cell.bg));
+                queue!(out, style::SetColors(style::Colors::new(fg, bg)))?;
+                colors = Some((cell.fg, cell.bg));
+            }
+            queue!(out, style::Print(cell.char))?;
+        }
+        out.flush()?;
+        self.drawn.clone_from(&self.cells);
+        Ok(())
+    }
+}
+
+fn color(rgb: &Rgb) -> style::Color {
+    style::Color::Rgb {
+        r: rgb.0,
+        g: rgb.1,
+        b: rgb.2,
+    }
+}
+
+impl UiCtx<String> for TuiCtx {
+    fn window_size(&self) -> Result<(i32, i32), String> {
+        Ok((self.width as i32, self.height as i32 * ROW))
+    }
+
+    fn scale(&self) -> f32 {
+        1.0 / 8.0
+    }
+
+    fn fill_rect(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        rgb: &Rgb,
+    ) -> Result<(), String> {
+        for row in y.div_euclid(ROW)..(y + height + ROW - 1).div_euclid(ROW) {
+            for x in x..x + width {
+                if let Some(cell) = self.cell(x, row) {
+                    *cell = Cell {
+                        char: ' ',
+                        fg: *rgb,
+                        bg: *rgb,
+                    };
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn outline_rect(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        rgb: &Rgb,
+    ) -> Result<(), String> {
+        let (left, right) = (x, x + width - 1);
+        let (top, bottom) = (y.div_euclid(ROW), (y + height - 1).div_euclid(ROW));
+        let mut set = |x: i32, row: i32, char: char| {
+            if let Some(cell) = self.cell(x, row) {
+                cell.char = char;
+                cell.fg = *rgb;
+            }
+        };
+        if top == bottom {
+            set(left, top, '[');
+            set(right, top, ']');
+            return Ok(());
+        }
+        for x in left + 1..right {
+            set(x, top, '─');
+            set(x, bottom, '─');
+        }
+        for row in top + 1..bottom {
+            set(left, row, '│');
+            set(right, row, '│');
+        }
+        set(left, top, '┌');
+        set(right, top, '┐');
+        set(left, bottom, '└');
+        set(right, bottom, '┘');
+        Ok(())
+    }
+
+    fn text_size<Text: AsRef<str>>(&mut self, _: &Font, text: Text) -> Result<(i32, i32), String> {
+        Ok((text.as_ref().chars().count() as i32, ROW))
+    }
+
+    fn fill_text<Text: AsRef<str>>(
+        &mut self,
+        _: &Font,
+        text: Text,
+        x: i32,
+        y: i32,
+        width: i32,
+        _: i32,
+        rgb: &Rgb,
+    ) -> Result<(), String> {
+        let row = y.div_euclid(ROW);
+        for (offset, char) in text
+            .as_ref()
+            .chars()
+            .take(width.max(0) as usize)
+            .enumerate()
+        {
+            if let Some(cell) = self.cell(x + offset as i32, row) {
+                cell.char = char;
+                cell.fg = *rgb;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw_image(
+        &mut self,
+        image: &Image,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> Result<(), String> {
+        let pixels = (image.rgba.len() / 4).max(1) as u32;
+        let mut sum = [0u32; 3];
+        for pixel in image.rgba.chunks_exact(4) {
+            for (sum, channel) in sum.iter_mut().zip(pixel) {
+                *sum += *channel as u32;
+            }
+        }
+        let [r, g, b] = sum.map(|channel| (channel / pixels) as u8);
+        self.fill_rect(x, y, width, height, &Rgb(r, g, b))
+    }
+
+    fn clear(&mut self, rgb: &Rgb) -> Result<(), String> {
+        self.cells.fill(Cell {
+            char: ' ',
+            fg: *rgb,
+            bg: *rgb,
+        });
+        Ok(())
+    }
+}
+
+struct Keyboard {
+    enhanced: bool,
+    held: HashMap<Key, Instant>,
+}
+
+impl Keyboard {
+    // well below the das delay, so a tap without release events moves once
+    const RELEASE_AFTER: Duration = Duration::from_millis(50);
+
+    fn handle(&mut self, key: Key, kind: KeyEventKind, now: Instant) -> Vec<(Binding, bool)> {
+        let binding = Binding::Key(key);
+        if kind == KeyEventKind::Release {
+            return self
+                .held
+                .remove(&key)
+                .map(|_| (binding, false))
+                .into_iter()
+                .collect();
+        }
+        let repeated = self.held.insert(key, now + Self::RELEASE_AFTER).is_some();
+        match (repeated, self.enhanced) {
+            (false, _) => vec![(binding, true)],
+            (true, true) => Vec::new(),
+            // the terminal's own key repeat stands in for auto-shift
+            (true, false) => vec![(binding.clone(), false), (binding, true)],
+        }
+    }
+
+    fn expired(&mut self, now: Instant) -> Vec<(Binding, bool)> {
+        if self.enhanced {
+            return Vec::new();
+        }
+        let mut released = Vec::new();
+        self.held.retain(|key, release_at| {
+            let held = *release_at > now;
+            if !held {
+                released.push((Binding::Key(*key), false));
+            }
+            held
+        });
+        released
+    }
+}
+
+struct Terminal {
+    enhanced: bool,
+}
+
+impl Terminal {
+    fn enter() -> Result<Self, String> {
+        terminal::enable_raw_mode().map_err(|err| err.to_string())?;
+        let enhanced = terminal::supports_keyboard_enhancement().unwrap_or(false);
+        let terminal = Self { enhanced };
+        let mut out = std::io::stdout();
+        queue!(out, terminal::EnterAlternateScreen, cursor::Hide).map_err(|err| err.to_string())?;
+        if enhanced {
+            let flags = KeyboardEnhancementFlags::REPORT_EVENT_TYPES;
+            queue!(out, PushKeyboardEnhancementFlags(flags)).map_err(|err| err.to_string())?;
+        }
+        out.flush().map_err(|err| err.to_string())?;
+        Ok(terminal)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let mut out = std::io::stdout();
+        if self.enhanced {
+            let _ = queue!(out, PopKeyboardEnhancementFlags);
+        }
+        let _ = queue!(
+            out,
+            style::ResetColor,
+            cursor::Show,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = out.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub fn start_terminal_game(
+    config: Config,
+    config_path: PathBuf,
+    notices: Vec<String>,
+    settings: Settings,
+    replay: Option<Replay>,
+    cli: Cli,
+) -> Result<(), String> {
+    let mut session = Session::new(settings, replay, cli.record.is_some());
+    let result = run_terminal_game(config, config_path, notices, &mut session);
+    result.and(session.save_recording(cli.record.as_deref()))
+}
+
+fn run_terminal_game(
+    mut config: Config,
+    config_path: PathBuf,
+    mut notices: Vec<String>,
+    session: &mut Session,
+) -> Result<(), String> {
+    let assets = Assets::new()?;
+    let mut theme = Theme::load(&assets, &config.theme).unwrap_or_else(|err| {
+        notices.push(format!("theme: {err}, using the default theme"));
+        Theme::default()
+    });
+    theme.glyphs = false;
+    let font = Font::new("terminal", Cow::Borrowed(&[]), 1);
+
+    let terminal = Terminal::enter()?;
+    let mut keyboard = Keyboard {
+        enhanced: terminal.enhanced,
+        held: HashMap::new(),
+    };
+    let (width, height) = terminal::size().map_err(|err| err.to_string())?;
+    let mut ctx = TuiCtx::new(width, height);
+    let mut out = std::io::stdout();
+    let mut timestep =
+        FixedTimestep::new(config.timing.tick_rate, config.timing.max_steps_per_frame);
+    let mut notices_until = Instant::now() + Duration::from_secs(10);
+    let frame = Duration::from_secs_f64(1.0 / config.timing.fps_cap.unwrap_or(60) as f64);
+
+    'running: loop {
+        let frame_start = Instant::now();
+
+        let mut bindings = keyboard.expired(Instant::now());
+        while event::poll(Duration::ZERO).map_err(|err| err.to_string())? {
+            match event::read().map_err(|err| err.to_string())? {
+                TermEvent::Key(key) => {
+                    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                    if ctrl && matches!(key.code, KeyCode::Char('c' | 'q')) {
+                        break 'running;
+                    }
+                    if let Some(code) = Key::from_crossterm(key.code) {
+                        bindings.extend(keyboard.handle(code, key.kind, Instant::now()));
+                    }
+                }
+                TermEvent::Resize(width, height) => ctx = TuiCtx::new(width, height),
+                _ => {}
+            }
+        }
+
+        for (binding, pressed) in bindings {
+            let handled = session.handle(&mut config, binding, pressed, Instant::now());
+            if handled.config_changed {
+                if let Err(err) = config.save(&config_path) {
+                    notices.push(format!("could not save config: {err}"));
+                    notices_until = Instant::now() + Duration::from_secs(10);
+                }
+            }
+        }
+        session.countdown_beat(Instant::now());
+
+        if session.frozen() {
+            timestep.reset();
+        } else {
+            for _ in 0..timestep.advance() {
+                if session.step().is_none() || session.game.game_over {
+                    break;
+                }
+            }
+        }
+
+        ctx.draw_game(&font, &session.game, &theme)?;
+        if let Some(menu) = &session.options {
+            let (lines, selected) = menu.lines(&config, Binding::to_string);
+            ctx.draw_menu(&font, &lines, selected, &theme)?;
+        } else if session.paused {
+            let keys = prompt(&config.pause, Binding::to_string);
+            ctx.draw_important_text(&font, format!("paused, press [{keys}]"), &theme)?;
+        } else if let Some((_, beat)) = session.countdown {
+            ctx.draw_important_text(&font, format!("{beat}..."), &theme)?;
+        } else if session.game.game_over {
+            let keys = prompt(&config.restart, Binding::to_string);
+            ctx.draw_important_text(&font, format!("game over, press [{keys}]"), &theme)?;
+        }
+        if !notices.is_empty() && Instant::now() < notices_until {
+            ctx.draw_notices(&font, &notices, &theme)?;
+        }
+        ctx.present(&mut out).map_err(|err| err.to_string())?;
+
+        if let Some(remaining) = frame.checked_sub(frame_start.elapsed()) {
+            event::poll(remaining).map_err(|err| err.to_string())?;
+        }
+    }
+
+    drop(terminal);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Keyboard, TuiCtx};
+    use crate::actions::ActionsHeld;
+    use crate::config::{Binding, Config, Key};
+    use crate::game::Game;
+    use crate::gui::theme::Theme;
+    use crate::gui::ui::{Font, GameUiCtx};
+    use crossterm::event::KeyEventKind;
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    fn text(ctx: &TuiCtx, row: usize, x: usize, len: usize) -> String {
+        let start = row * ctx.width as usize + x;
+        ctx.cells[start..start + len]
+            .iter()
+            .map(|cell| cell.char)
+            .collect()
+    }
+
+    #[test]
+    fn renders_game_into_cells() {
+        let config = Config::default();
+        let game = Game::new(config.settings(config.mode));
+        let theme = Theme::default();
+        let font = Font::new("terminal", Cow::Borrowed(&[]), 1);
+
+        let mut ctx = TuiCtx::new(80, 24);
+        ctx.draw_board(&game, None, &theme).unwrap();
+        ctx.draw_bag(&game.held_tetromino, &game.next_tetrominos, &theme)
+            .unwrap();
+        ctx.draw_score(&font, &game.score, game.grade(), &theme)
+            .unwrap();
+
+        assert_eq!(text(&ctx, 1, 29, 22), format!("┌{}┐", "─".repeat(20)));
+        assert_eq!(text(&ctx, 22, 29, 22), format!("└{}┘", "─".repeat(20)));
+        assert_eq!(text(&ctx, 3, 52, 8), "level: 0");
+        let board = ctx.cells[2 * 80 + 30];
+        assert_eq!(board.bg, theme.board);
+    }
+
+    #[test]
+    fn taps_move_once_and_terminal_repeats_shift() {
+        let config = Config::default();
+        let mut game = Game::new(config.settings(config.mode));
+        let mut actions = ActionsHeld::new();
+        let mut keyboard = Keyboard {
+            enhanced: false,
+            held: HashMap::new(),
+        };
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        let x = game.current_tetromino.x;
+        for tick in 0..60 {
+            let now = at(tick * 1000 / 60);
+            let mut bindings = keyboard.expired(now);
+            if tick == 0 {
+                bindings.extend(keyboard.handle(Key::Left, KeyEventKind::Press, now));
+            }
+            for (binding, pressed) in bindings {
+                for action in config.actions(&binding) {
+                    match pressed {
+                        true => actions.insert(action, game.ticks),
+                        false => actions.remove(&action),
+                    };
+                }
+            }
+            game.step(&actions);
+        }
+        assert_eq!(game.current_tetromino.x, x - 1);
+        assert!(actions.is_empty());
+
+        let left = || Binding::Key(Key::Left);
+        assert!(keyboard.expired(at(2000)).is_empty());
+        assert_eq!(
+            keyboard.handle(Key::Left, KeyEventKind::Repeat, at(2500)),
+            vec![(left(), true)]
+        );
+        assert_eq!(
+            keyboard.handle(Key::Left, KeyEventKind::Repeat, at(2530)),
+            vec![(left(), false), (left(), true)]
+        );
+        assert_eq!(keyboard.expired(at(2600)), vec![(left(), false)]);
+
+        keyboard.enhanced = true;
+        assert_eq!(
+            keyboard.handle(Key::Left, KeyEventKind::Press, at(3000)),
+            vec![(left(), true)]
+        );
+        assert!(keyboard
+            .handle(Key::Left, KeyEventKind::Repeat, at(3500))
+            .is_empty());
+        assert!(keyboard.expired(at(9000)).is_empty());
+        assert_eq!(
+            keyboard.handle(Key::Left, KeyEventKind::Release, at(9100)),
+            vec![(left(), false)]
+        );
+    }
+}
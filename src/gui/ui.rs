@@ -1,11 +1,21 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use super::layout::Layout;
+use super::motion::Pose;
+use super::theme::{GhostStyle, Theme};
 use crate::{
-    board::Board,
-    game::{CurrentTetromino, Score},
+    game::{Game, Phase},
+    scoring::Score,
     tetromino::{Direction, Tetromino},
 };
 
 pub trait UiCtx<Err> {
     fn window_size(&self) -> Result<(i32, i32), Err>;
+    fn scale(&self) -> f32;
     fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, rgb: &Rgb) -> Result<(), Err>;
     fn outline_rect(
         &mut self,
@@ -15,15 +25,21 @@ pub trait UiCtx<Err> {
         height: i32,
         rgb: &Rgb,
     ) -> Result<(), Err>;
-    fn text_size<P: AsRef<std::path::Path>, Text: AsRef<str>>(
+    fn text_size<Text: AsRef<str>>(&mut self, font: &Font, text: Text) -> Result<(i32, i32), Err>;
+    #[allow(clippy::too_many_arguments)]
+    fn fill_text<Text: AsRef<str>>(
         &mut self,
-        font: P,
+        font: &Font,
         text: Text,
-    ) -> Result<(i32, i32), Err>;
-    fn fill_text<P: AsRef<std::path::Path>, Text: AsRef<str>>(
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        rgb: &Rgb,
+    ) -> Result<(), Err>;
+    fn draw_image(
         &mut self,
-        font: P,
-        text: Text,
+        image: &Image,
         x: i32,
         y: i32,
         width: i32,
@@ -33,18 +49,27 @@ pub trait UiCtx<Err> {
 }
 
 pub trait GameUiCtx<Err>: UiCtx<Err> {
-    fn tile_size(&self) -> i32 {
-        24
+    fn layout(&self) -> Result<Layout, Err> {
+        let (width, height) = self.window_size()?;
+        Ok(Layout::new(width, height, self.scale()))
+    }
+
+    fn font(&self, font: &Font) -> Result<Font, Err> {
+        Ok(Font {
+            size: self.layout()?.font_size,
+            ..font.clone()
+        })
     }
 
     fn draw_tetromino_from_parts(
         &mut self,
         x: i8,
         y: i8,
-        color: Rgb,
+        tetromino: &Tetromino,
         pattern: &Vec<(usize, usize)>,
-        filled: bool,
+        theme: &Theme,
     ) -> Result<(), Err> {
+        let color = theme.piece(tetromino);
         for (x_offset, y_offset) in pattern {
             let x = *x_offset as i8 + x;
             let y = *y_offset as i8 + y;
@@ -53,19 +78,67 @@ pub trait GameUiCtx<Err>: UiCtx<Err> {
                 continue;
             }
 
-            self.draw_board_tile(x as i32, y as i32, &color, filled)?
+            self.draw_piece_tile(x as i32, y as i32, tetromino, &color, theme)?
+        }
+        Ok(())
+    }
+
+    fn board_tile_position(&self, x: i32, y: i32) -> Result<(i32, i32), Err> {
+        let layout = self.layout()?;
+        Ok((
+            layout.board.x + x * layout.tile,
+            layout.board.y + y * layout.tile,
+        ))
+    }
+
+    fn draw_piece_tile(
+        &mut self,
+        x: i32,
+        y: i32,
+        tetromino: &Tetromino,
+        color: &Rgb,
+        theme: &Theme,
+    ) -> Result<(), Err> {
+        let (x, y) = self.board_tile_position(x, y)?;
+        self.draw_tile(x, y, tetromino, color, theme)
+    }
+
+    fn draw_tile(
+        &mut self,
+        x: i32,
+        y: i32,
+        tetromino: &Tetromino,
+        color: &Rgb,
+        theme: &Theme,
+    ) -> Result<(), Err> {
+        let size = self.layout()?.tile;
+        match &theme.skin {
+            Some(skin) => self.draw_image(skin.piece(tetromino), x, y, size, size)?,
+            None => self.fill_rect(x, y, size, size, color)?,
+        }
+        if !theme.glyphs {
+            return Ok(());
+        }
+        let glyph_color = color.contrast();
+        for (glyph_x, glyph_y, width, height) in Theme::glyph(tetromino) {
+            self.fill_rect(
+                x + glyph_x * size / 8,
+                y + glyph_y * size / 8,
+                (width * size / 8).max(1),
+                (height * size / 8).max(1),
+                &glyph_color,
+            )?;
         }
         Ok(())
     }
 
     fn draw_board_tile(&mut self, x: i32, y: i32, color: &Rgb, filled: bool) -> Result<(), Err> {
-        let (win_width, win_height) = self.window_size()?;
-        let x = center(self.tile_size() * Board::WIDTH as i32, win_width) + x * self.tile_size();
-        let y = center(self.tile_size() * Board::HEIGHT as i32, win_height) + y * self.tile_size();
+        let (x, y) = self.board_tile_position(x, y)?;
+        let size = self.layout()?.tile;
         if filled {
-            self.fill_rect(x, y, self.tile_size(), self.tile_size(), color)?;
+            self.fill_rect(x, y, size, size, color)?;
         } else {
-            self.outline_rect(x, y, self.tile_size(), self.tile_size(), color)?;
+            self.outline_rect(x, y, size, size, color)?;
         }
         Ok(())
     }
@@ -77,8 +150,9 @@ pub trait GameUiCtx<Err>: UiCtx<Err> {
         y: i32,
         width: i32,
         height: i32,
+        theme: &Theme,
     ) -> Result<(), Err> {
-        let color = Rgb::from_tetromino(tetromino);
+        let color = theme.piece(tetromino);
         let pattern = tetromino.pattern(&Direction::Up);
 
         let min_x_offset = pattern
@@ -111,15 +185,16 @@ pub trait GameUiCtx<Err>: UiCtx<Err> {
             )
         };
 
-        let x = x + center(self.tile_size() * x_len as i32, width);
-        let y = y + center(self.tile_size() * y_len as i32, height);
+        let tile = self.layout()?.tile;
+        let x = x + center(tile * x_len as i32, width);
+        let y = y + center(tile * y_len as i32, height);
 
         for (x_offset, y_offset) in pattern {
             let x_offset = (x_offset - min_x_offset) as i32;
             let y_offset = (y_offset - min_y_offset) as i32;
-            let x = x + (x_offset * self.tile_size());
-            let y = y + (y_offset * self.tile_size());
-            self.fill_rect(x, y, self.tile_size(), self.tile_size(), &color)?;
+            let x = x + (x_offset * tile);
+            let y = y + (y_offset * tile);
+            self.draw_tile(x, y, tetromino, &color, theme)?;
         }
 
         Ok(())
@@ -132,14 +207,15 @@ pub trait GameUiCtx<Err>: UiCtx<Err> {
         y: i32,
         width: i32,
         height: i32,
+        theme: &Theme,
     ) -> Result<(), Err> {
-        self.fill_rect(x, y, width, height, &Rgb(0, 0, 0))?;
-        self.outline_rect(x - 1, y - 1, width + 2, height + 2, &Rgb(255, 255, 255))?;
+        self.fill_rect(x, y, width, height, &theme.board)?;
+        self.outline_rect(x - 1, y - 1, width + 2, height + 2, &theme.border)?;
 
         let Some(tetromino) = held else {
             return Ok(());
         };
-        self.draw_centered_tetromino(&tetromino, x, y, width, height)?;
+        self.draw_centered_tetromino(tetromino, x, y, width, height, theme)?;
 
         Ok(())
     }
@@ -151,121 +227,326 @@ pub trait GameUiCtx<Err>: UiCtx<Err> {
         y: i32,
         width: i32,
         height: i32,
+        theme: &Theme,
     ) -> Result<(), Err> {
-        self.fill_rect(x, y, width, height * 3, &Rgb(0, 0, 0))?;
-        self.outline_rect(x - 1, y - 1, width + 2, height * 3 + 2, &Rgb(255, 255, 255))?;
+        self.fill_rect(x, y, width, height * 3, &theme.board)?;
+        self.outline_rect(x - 1, y - 1, width + 2, height * 3 + 2, &theme.border)?;
 
         for (offset, tetromino) in next_up.iter().enumerate() {
-            self.draw_centered_tetromino(tetromino, x, y + offset as i32 * height, width, height)?;
+            let y = y + offset as i32 * height;
+            self.draw_centered_tetromino(tetromino, x, y, width, height, theme)?;
         }
 
         Ok(())
     }
 
-    fn draw_bag(&mut self, held: &Option<Tetromino>, next_up: &[Tetromino; 3]) -> Result<(), Err> {
-        let (win_width, win_height) = self.window_size()?;
-        let x = center(self.tile_size() * Board::WIDTH as i32, win_width);
-        let y = center(self.tile_size() * Board::HEIGHT as i32, win_height);
-
-        let width = self.tile_size() * 5;
-        let height = self.tile_size() * 4;
-        let x = x - width - self.tile_size();
-
-        self.draw_held_tetromino(&held, x, y, width, height)?;
+    fn draw_bag(
+        &mut self,
+        held: &Option<Tetromino>,
+        next_up: &[Tetromino; 3],
+        theme: &Theme,
+    ) -> Result<(), Err> {
+        let layout = self.layout()?;
+        let (hold, queue) = (layout.hold, layout.queue);
 
-        let y = y + height + self.tile_size();
+        self.draw_held_tetromino(held, hold.x, hold.y, hold.width, hold.height, theme)?;
 
-        self.draw_next_up(next_up, x, y, width, height)?;
+        let height = queue.height / next_up.len() as i32;
+        self.draw_next_up(next_up, queue.x, queue.y, queue.width, height, theme)?;
 
         Ok(())
     }
 
-    fn draw_score<P: AsRef<std::path::Path>>(&mut self, font: P, score: &Score) -> Result<(), Err> {
-        let (win_width, win_height) = self.window_size()?;
-        let board_width = self.tile_size() * Board::WIDTH as i32;
-        let board_height = self.tile_size() * Board::HEIGHT as i32;
-        let x = center(board_width, win_width) + board_width + self.tile_size();
-        let y = center(board_height, win_height) + self.tile_size();
-
-        let level = format!("level: {}", score.level);
-        let lines = format!("lines: {}", score.lines);
-        let points = format!("points: {}", score.points);
+    fn draw_score(
+        &mut self,
+        font: &Font,
+        score: &Score,
+        grade: Option<&str>,
+        theme: &Theme,
+    ) -> Result<(), Err> {
+        let layout = self.layout()?;
+        let font = self.font(font)?;
+        let stats = layout.stats;
+
+        let mut lines = vec![
+            format!("level: {}", score.level),
+            format!("lines: {}", score.lines),
+            format!("points: {}", score.points),
+        ];
+        if let Some(grade) = grade {
+            lines.push(format!("grade: {grade}"));
+        }
+        if layout.compact {
+            lines = vec![lines.join("  ")];
+        }
 
-        let level_size = self.text_size(font.as_ref(), &level)?;
-        let lines_size = self.text_size(font.as_ref(), &lines)?;
-        let points_size = self.text_size(font.as_ref(), &points)?;
+        let mut y = stats.y;
+        for line in lines {
+            let (width, height) = self.text_size(&font, &line)?;
+            self.fill_text(&font, line, stats.x, y, width, height, &theme.text)?;
+            y += height + layout.tile;
+        }
 
-        self.fill_text(font.as_ref(), level, x, y, level_size.0, level_size.1)?;
-        let y = y + level_size.1 + self.tile_size();
-        self.fill_text(font.as_ref(), lines, x, y, lines_size.0, lines_size.1)?;
-        let y = y + lines_size.1 + self.tile_size();
-        self.fill_text(font.as_ref(), points, x, y, points_size.0, points_size.1)?;
+        Ok(())
+    }
 
+    fn draw_posed_tetromino(
+        &mut self,
+        tetromino: &Tetromino,
+        pattern: &[(usize, usize)],
+        pose: &Pose,
+        theme: &Theme,
+    ) -> Result<(), Err> {
+        let layout = self.layout()?;
+        let tile = layout.tile as f32;
+        let color = theme.piece(tetromino);
+        let count = pattern.len() as f32;
+        let center_x = pattern.iter().map(|(x, _)| *x as f32).sum::<f32>() / count;
+        let center_y = pattern.iter().map(|(_, y)| *y as f32).sum::<f32>() / count;
+        let (sin, cos) = pose.angle.sin_cos();
+        for (x, y) in pattern {
+            let (dx, dy) = (*x as f32 - center_x, *y as f32 - center_y);
+            let x = pose.x + center_x + dx * cos - dy * sin;
+            let y = pose.y + center_y + dx * sin + dy * cos;
+            if y < 0.0 {
+                continue;
+            }
+            let x = layout.board.x + (x * tile).round() as i32;
+            let y = layout.board.y + (y * tile).round() as i32;
+            self.draw_tile(x, y, tetromino, &color, theme)?;
+        }
         Ok(())
     }
 
-    fn draw_board(&mut self, board: &Board, current: &CurrentTetromino) -> Result<(), Err> {
-        let (win_width, win_height) = self.window_size()?;
+    fn draw_board(&mut self, game: &Game, pose: Option<&Pose>, theme: &Theme) -> Result<(), Err> {
+        let board = &game.board;
+        let current = &game.current_tetromino;
+        let area = self.layout()?.board;
         self.outline_rect(
-            center(self.tile_size() * Board::WIDTH as i32, win_width) - 1,
-            center(self.tile_size() * Board::HEIGHT as i32, win_height) - 1,
-            self.tile_size() * Board::WIDTH as i32 + 2,
-            self.tile_size() * Board::HEIGHT as i32 + 2,
-            &Rgb(255, 255, 255),
+            area.x - 1,
+            area.y - 1,
+            area.width + 2,
+            area.height + 2,
+            &theme.border,
         )?;
 
+        let flashing_rows: &[usize] = match &game.phase {
+            Phase::LineClear { rows, .. } if (game.ticks / 4).is_multiple_of(2) => rows,
+            _ => &[],
+        };
+
         for (y, row) in board.iter().enumerate() {
             for (x, piece) in row.iter().enumerate() {
-                let color = match piece {
-                    Some(t) => Rgb::from_tetromino(t),
-                    None => Rgb(0, 0, 0),
-                };
-                self.draw_board_tile(x as i32, y as i32, &color, true)?
+                let (x, y, flashing) = (x as i32, y as i32, flashing_rows.contains(&y));
+                match piece {
+                    Some(_) if flashing => self.draw_board_tile(x, y, &theme.flash, true)?,
+                    Some(t) => self.draw_piece_tile(x, y, t, &theme.piece(t), theme)?,
+                    None => {
+                        self.draw_board_tile(x, y, &theme.board, true)?;
+                        if let Some(grid) = &theme.grid {
+                            self.draw_board_tile(x, y, grid, false)?;
+                        }
+                    }
+                }
             }
         }
 
+        if !matches!(game.phase, Phase::Falling) {
+            return Ok(());
+        }
+
         let pattern = current.tetromino.pattern(&current.direction);
 
-        self.draw_tetromino_from_parts(
-            current.x,
-            board.lowest_y(&current),
-            Rgb(255, 255, 255),
-            &pattern,
-            false,
+        if game.mode.ghost() && theme.ghost != GhostStyle::Hidden {
+            let color = theme
+                .ghost_color
+                .unwrap_or_else(|| theme.piece(&current.tetromino));
+            let filled = theme.ghost == GhostStyle::Filled;
+            let ghost_y = board.lowest_y(current);
+            for (x_offset, y_offset) in &pattern {
+                let x = (*x_offset as i8 + current.x) as i32;
+                let y = *y_offset as i8 + ghost_y;
+                if y < 0 {
+                    continue;
+                }
+                match &theme.skin {
+                    Some(skin) => {
+                        let (x, y) = self.board_tile_position(x, y as i32)?;
+                        let size = self.layout()?.tile;
+                        self.draw_image(skin.ghost(), x, y, size, size)?;
+                    }
+                    None => self.draw_board_tile(x, y as i32, &color, filled)?,
+                }
+            }
+        }
+
+        match pose {
+            Some(pose) => self.draw_posed_tetromino(&current.tetromino, &pattern, pose, theme)?,
+            None => self.draw_tetromino_from_parts(
+                current.x,
+                current.y,
+                &current.tetromino,
+                &pattern,
+                theme,
+            )?,
+        }
+
+        Ok(())
+    }
+
+    fn draw_game(&mut self, font: &Font, game: &Game, theme: &Theme) -> Result<(), Err> {
+        self.draw_game_posed(font, game, None, theme)
+    }
+
+    fn draw_game_posed(
+        &mut self,
+        font: &Font,
+        game: &Game,
+        pose: Option<&Pose>,
+        theme: &Theme,
+    ) -> Result<(), Err> {
+        self.clear(&theme.background)?;
+        self.draw_board(game, pose, theme)?;
+        self.draw_bag(&game.held_tetromino, &game.next_tetrominos, theme)?;
+        self.draw_score(font, &game.score, game.grade(), theme)
+    }
+
+    fn draw_debug_text<Text: AsRef<str>>(
+        &mut self,
+        font: &Font,
+        text: Text,
+        theme: &Theme,
+    ) -> Result<(), Err> {
+        let font = &self.font(font)?;
+        let (width, height) = self.text_size(font, text.as_ref())?;
+        let padding = self.layout()?.padding;
+        self.fill_rect(
+            0,
+            0,
+            width + padding * 2,
+            height + padding * 2,
+            &theme.board,
+        )?;
+        self.fill_text(font, text, padding, padding, width, height, &theme.text)?;
+        Ok(())
+    }
+
+    fn draw_indicator<Text: AsRef<str>>(
+        &mut self,
+        font: &Font,
+        text: Text,
+        theme: &Theme,
+    ) -> Result<(), Err> {
+        let font = &self.font(font)?;
+        let (win_width, _) = self.window_size()?;
+        let (width, height) = self.text_size(font, text.as_ref())?;
+        let padding = self.layout()?.padding;
+        let x = win_width - width - padding * 2;
+        self.fill_rect(
+            x,
+            0,
+            width + padding * 2,
+            height + padding * 2,
+            &theme.board,
         )?;
+        self.fill_text(font, text, x + padding, padding, width, height, &theme.text)?;
+        Ok(())
+    }
+
+    fn draw_notices(&mut self, font: &Font, notices: &[String], theme: &Theme) -> Result<(), Err> {
+        let font = &self.font(font)?;
+        let (_, win_height) = self.window_size()?;
+        let padding = self.layout()?.padding;
+        let mut y = win_height - padding;
+        for line in notices.iter().flat_map(|notice| notice.lines()).rev() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (width, height) = self.text_size(font, line)?;
+            y -= height;
+            self.fill_rect(0, y, width + padding * 2, height, &theme.board)?;
+            self.fill_text(font, line, padding, y, width, height, &theme.text)?;
+        }
+        Ok(())
+    }
 
-        self.draw_tetromino_from_parts(
-            current.x,
-            current.y,
-            Rgb::from_tetromino(&current.tetromino),
-            &pattern,
-            true,
+    fn draw_menu(
+        &mut self,
+        font: &Font,
+        lines: &[String],
+        selected: usize,
+        theme: &Theme,
+    ) -> Result<(), Err> {
+        let font = &self.font(font)?;
+        let (win_width, win_height) = self.window_size()?;
+        let padding = self.layout()?.padding;
+
+        let mut sizes = Vec::with_capacity(lines.len());
+        for line in lines {
+            let size = if line.is_empty() {
+                (0, font.size as i32)
+            } else {
+                self.text_size(font, line)?
+            };
+            sizes.push(size);
+        }
+        let width = sizes.iter().map(|(width, _)| *width).max().unwrap_or(0);
+        let height = sizes.iter().map(|(_, height)| *height).sum::<i32>();
+
+        let x = center(width, win_width);
+        let mut y = center(height, win_height);
+
+        self.outline_rect(
+            x - padding - 1,
+            y - padding - 1,
+            width + padding * 2 + 2,
+            height + padding * 2 + 2,
+            &theme.panel_border,
+        )?;
+        self.fill_rect(
+            x - padding,
+            y - padding,
+            width + padding * 2,
+            height + padding * 2,
+            &theme.panel,
         )?;
 
+        for (i, (line, (width, height))) in lines.iter().zip(sizes).enumerate() {
+            if i == selected {
+                self.outline_rect(x - 4, y, width + 8, height, &theme.panel_border)?;
+            }
+            if !line.is_empty() {
+                self.fill_text(font, line, x, y, width, height, &theme.text)?;
+            }
+            y += height;
+        }
+
         Ok(())
     }
 
-    fn draw_important_text<P: AsRef<std::path::Path>, Text: AsRef<str>>(
+    fn draw_important_text<Text: AsRef<str>>(
         &mut self,
-        font: P,
+        font: &Font,
         text: Text,
+        theme: &Theme,
     ) -> Result<(), Err> {
+        let font = &self.font(font)?;
         let (win_width, win_height) = self.window_size()?;
-        let size = self.text_size(font.as_ref(), text.as_ref())?;
+        let size = self.text_size(font, text.as_ref())?;
         let width = size.0;
         let height = size.1;
 
         let x = center(width, win_width);
         let y = center(height, win_height);
 
-        let padding = 8;
+        let padding = self.layout()?.padding;
 
         self.outline_rect(
             x - padding - 1,
             y - padding - 1,
             width + padding * 2 + 2,
             height + padding * 2 + 2,
-            &Rgb(255, 255, 255),
+            &theme.panel_border,
         )?;
 
         self.fill_rect(
@@ -273,9 +554,9 @@ pub trait GameUiCtx<Err>: UiCtx<Err> {
             y - padding,
             width + padding * 2,
             height + padding * 2,
-            &Rgb(16, 16, 16),
+            &theme.panel,
         )?;
-        self.fill_text(font, text, x, y, width, height)?;
+        self.fill_text(font, text, x, y, width, height, &theme.text)?;
 
         Ok(())
     }
@@ -283,18 +564,139 @@ pub trait GameUiCtx<Err>: UiCtx<Err> {
 
 impl<T, Err> GameUiCtx<Err> for T where T: UiCtx<Err> {}
 
+pub struct Offset<'a, Ctx> {
+    pub ctx: &'a mut Ctx,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl<Ctx: UiCtx<Err>, Err> UiCtx<Err> for Offset<'_, Ctx> {
+    fn window_size(&self) -> Result<(i32, i32), Err> {
+        self.ctx.window_size()
+    }
+
+    fn scale(&self) -> f32 {
+        self.ctx.scale()
+    }
+
+    fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, rgb: &Rgb) -> Result<(), Err> {
+        self.ctx
+            .fill_rect(x + self.x, y + self.y, width, height, rgb)
+    }
+
+    fn outline_rect(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        rgb: &Rgb,
+    ) -> Result<(), Err> {
+        self.ctx
+            .outline_rect(x + self.x, y + self.y, width, height, rgb)
+    }
+
+    fn text_size<Text: AsRef<str>>(&mut self, font: &Font, text: Text) -> Result<(i32, i32), Err> {
+        self.ctx.text_size(font, text)
+    }
+
+    fn fill_text<Text: AsRef<str>>(
+        &mut self,
+        font: &Font,
+        text: Text,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        rgb: &Rgb,
+    ) -> Result<(), Err> {
+        self.ctx
+            .fill_text(font, text, x + self.x, y + self.y, width, height, rgb)
+    }
+
+    fn draw_image(
+        &mut self,
+        image: &Image,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> Result<(), Err> {
+        self.ctx
+            .draw_image(image, x + self.x, y + self.y, width, height)
+    }
+
+    fn clear(&mut self, rgb: &Rgb) -> Result<(), Err> {
+        self.ctx.clear(rgb)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Font {
+    pub name: &'static str,
+    pub data: Arc<[u8]>,
+    pub size: u16,
+}
+
+impl Font {
+    pub fn new(name: &'static str, data: Cow<'static, [u8]>, size: u16) -> Self {
+        Self {
+            name,
+            data: Arc::from(data),
+            size,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub id: usize,
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            width,
+            height,
+            rgba,
+        }
+    }
+
+    pub fn encode_png(&self) -> Result<Vec<u8>, String> {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|err| err.to_string())?;
+        writer
+            .write_image_data(&self.rgba)
+            .map_err(|err| err.to_string())?;
+        writer.finish().map_err(|err| err.to_string())?;
+        Ok(png)
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let path = path.as_ref();
+        std::fs::write(path, self.encode_png()?)
+            .map_err(|err| format!("could not write '{}': {err}", path.display()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Rgb(pub u8, pub u8, pub u8);
 
 impl Rgb {
-    pub fn from_tetromino(tetromino: &Tetromino) -> Self {
-        match tetromino {
-            Tetromino::I => Self(0, 255, 255),
-            Tetromino::J => Self(0, 0, 255),
-            Tetromino::L => Self(255, 128, 0),
-            Tetromino::O => Self(255, 255, 0),
-            Tetromino::S => Self(0, 255, 0),
-            Tetromino::T => Self(255, 0, 255),
-            Tetromino::Z => Self(255, 0, 0),
+    pub fn contrast(&self) -> Self {
+        let luminance = 299 * self.0 as u32 + 587 * self.1 as u32 + 114 * self.2 as u32;
+        if luminance > 128_000 {
+            Self(0, 0, 0)
+        } else {
+            Self(255, 255, 255)
         }
     }
 }
@@ -1,21 +1,68 @@
+use cli::Cli;
 use config::Config;
+use replay::{Playback, Replay};
 use tetromino::Tetromino;
 
 mod actions;
 mod board;
+mod cli;
 mod config;
 mod game;
 mod gui;
+mod mode;
+mod randomizer;
+mod replay;
+mod scoring;
 mod tetromino;
 
 fn main() -> Result<(), String> {
-    let config = {
-        let base = xdg::BaseDirectories::new().map_err(|err| err.to_string())?;
-        let path = base
-            .place_config_file("reimtris2/config.toml")
-            .map_err(|err| err.to_string())?;
-        let config = Config::from_file(path)?;
-        config
+    let cli = Cli::parse(std::env::args().skip(1))?;
+    if cli.help {
+        print!("{}", cli::HELP);
+        return Ok(());
+    }
+
+    let replay = cli.replay.as_ref().map(Replay::load).transpose()?;
+    if cli.headless {
+        let replay = replay.expect("cli requires a replay when headless");
+        let game = Playback::new(replay).run();
+        println!(
+            "level: {}\nlines: {}\npoints: {}",
+            game.score.level, game.score.lines, game.score.points
+        );
+        return Ok(());
+    }
+
+    let path = match &cli.config {
+        Some(path) => path.clone(),
+        None => {
+            let base = xdg::BaseDirectories::new().map_err(|err| err.to_string())?;
+            base.place_config_file("reimtris2/config.toml")
+                .map_err(|err| err.to_string())?
+        }
+    };
+    let (config, notices) = Config::from_file(&path)?;
+
+    if let Some(dir) = &cli.export {
+        let replay = replay.expect("cli requires a replay when exporting");
+        let size = cli.size.unwrap_or((1280, 720));
+        let frames = gui::export_replay(&config, replay, dir, size, cli.fps.unwrap_or(30))?;
+        println!("wrote {frames} frames to {}", dir.display());
+        return Ok(());
+    }
+
+    let settings = match &replay {
+        Some(replay) => replay.settings(),
+        None => {
+            let mut settings = config.settings(cli.mode.unwrap_or(config.mode));
+            settings.level = cli.level;
+            settings.seed = cli.seed;
+            settings
+        }
     };
-    gui::start_game(config)
+
+    if cli.tui {
+        return gui::start_terminal_game(config, path, notices, settings, replay, cli);
+    }
+    gui::start_game(config, path, notices, settings, replay, cli)
 }
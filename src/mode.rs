@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+
+use crate::randomizer::{Bag, Randomizer, Reroll};
+use crate::scoring::{Guideline, Nes, Reimtris, ScoringRules, Tgm};
+use crate::tetromino::RotationSystem;
+
+pub struct Das {
+    pub delay: usize,
+    pub repeat: usize,
+}
+
+#[derive(Clone)]
+pub struct Settings {
+    pub mode: Mode,
+    pub entry_delay: usize,
+    pub line_clear_delay: usize,
+    pub seed: Option<u64>,
+    pub level: Option<usize>,
+}
+
+impl Settings {
+    pub fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            entry_delay: mode.entry_delay(),
+            line_clear_delay: mode.line_clear_delay(),
+            seed: None,
+            level: None,
+        }
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    #[default]
+    Reimtris,
+    Guideline,
+    Classic,
+    Tgm,
+}
+
+impl std::str::FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reimtris" => Ok(Mode::Reimtris),
+            "guideline" => Ok(Mode::Guideline),
+            "classic" => Ok(Mode::Classic),
+            "tgm" => Ok(Mode::Tgm),
+            _ => Err(format!(
+                "unknown mode '{s}', expected one of: reimtris, guideline, classic, tgm"
+            )),
+        }
+    }
+}
+
+impl Mode {
+    pub fn scoring_rules(&self) -> Box<dyn ScoringRules> {
+        match self {
+            Mode::Reimtris => Box::new(Reimtris),
+            Mode::Guideline => Box::new(Guideline),
+            Mode::Classic => Box::new(Nes),
+            Mode::Tgm => Box::new(Tgm),
+        }
+    }
+
+    pub fn randomizer(&self, seed: u64) -> Box<dyn Randomizer> {
+        match self {
+            Mode::Classic => Box::new(Reroll::new(seed)),
+            _ => Box::new(Bag::new(seed)),
+        }
+    }
+
+    pub fn rotation_system(&self) -> RotationSystem {
+        match self {
+            Mode::Classic => RotationSystem::Nintendo,
+            _ => RotationSystem::Super,
+        }
+    }
+
+    pub fn hold(&self) -> bool {
+        *self != Mode::Classic
+    }
+
+    pub fn hard_drop(&self) -> bool {
+        *self != Mode::Classic
+    }
+
+    pub fn ghost(&self) -> bool {
+        *self != Mode::Classic
+    }
+
+    pub fn das(&self) -> Das {
+        match self {
+            Mode::Classic => Das {
+                delay: 16,
+                repeat: 6,
+            },
+            _ => Das {
+                delay: 16,
+                repeat: 1,
+            },
+        }
+    }
+
+    pub fn gravity(&self, level: usize) -> usize {
+        match self {
+            Mode::Classic => match level {
+                0 => 48,
+                1 => 43,
+                2 => 38,
+                3 => 33,
+                4 => 28,
+                5 => 23,
+                6 => 18,
+                7 => 13,
+                8 => 8,
+                9 => 6,
+                10..=12 => 5,
+                13..=15 => 4,
+                16..=18 => 3,
+                19..=28 => 2,
+                _ => 1,
+            },
+            // tgm levels rise on every piece, gravity follows its table in 1/256 rows per frame
+            Mode::Tgm => {
+                let internal = match level {
+                    0..=29 => 4,
+                    30..=34 => 6,
+                    35..=39 => 8,
+                    40..=49 => 10,
+                    50..=59 => 12,
+                    60..=69 => 16,
+                    70..=79 => 32,
+                    80..=89 => 48,
+                    90..=99 => 64,
+                    100..=119 => 80,
+                    120..=139 => 96,
+                    140..=159 => 112,
+                    160..=169 => 128,
+                    170..=199 => 144,
+                    200..=219 => 4,
+                    220..=229 => 32,
+                    230..=232 => 64,
+                    233..=235 => 96,
+                    236..=238 => 128,
+                    239..=242 => 160,
+                    243..=246 => 192,
+                    247..=250 => 224,
+                    _ => 256,
+                };
+                256 / internal
+            }
+            _ => 32usize.saturating_sub(level * 2).max(1),
+        }
+    }
+
+    pub fn entry_delay(&self) -> usize {
+        match self {
+            Mode::Reimtris | Mode::Guideline => 0,
+            Mode::Classic => 10,
+            Mode::Tgm => 30,
+        }
+    }
+
+    pub fn line_clear_delay(&self) -> usize {
+        match self {
+            Mode::Reimtris | Mode::Guideline => 0,
+            Mode::Classic => 18,
+            Mode::Tgm => 41,
+        }
+    }
+
+    pub fn soft_drop_gravity(&self, level: usize) -> usize {
+        match self {
+            Mode::Classic => self.gravity(level).min(2),
+            _ => (self.gravity(level) / 10).max(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Mode;
+    use crate::scoring::{Lock, Score, Spin};
+
+    #[test]
+    fn classic_follows_the_nes_tables() {
+        let classic = Mode::Classic;
+        let frames = [48, 43, 38, 33, 28, 23, 18, 13, 8, 6];
+        for (level, frames) in frames.into_iter().enumerate() {
+            assert_eq!(classic.gravity(level), frames);
+        }
+        for (levels, frames) in [(10..=12, 5), (13..=15, 4), (16..=18, 3), (19..=28, 2)] {
+            for level in levels {
+                assert_eq!(classic.gravity(level), frames);
+            }
+        }
+        assert_eq!(classic.gravity(29), 1);
+        assert_eq!(classic.gravity(255), 1);
+        assert_eq!(classic.soft_drop_gravity(0), 2);
+        assert_eq!(classic.soft_drop_gravity(29), 1);
+
+        let das = classic.das();
+        assert_eq!((das.delay, das.repeat), (16, 6));
+        let das = Mode::Guideline.das();
+        assert_eq!((das.delay, das.repeat), (16, 1));
+    }
+
+    #[test]
+    fn tgm_gravity_follows_its_internal_level() {
+        let tgm = Mode::Tgm;
+        let rules = tgm.scoring_rules();
+        let mut score = Score::new(rules.initial_level());
+        let empty = Lock {
+            lines_cleared: 0,
+            spin: Spin::None,
+            perfect_clear: false,
+        };
+        for _ in 0..36 {
+            rules.lock(&mut score, &empty);
+        }
+        assert_eq!(score.level, 36);
+        assert_eq!(tgm.gravity(score.level), 32);
+        assert!(tgm.soft_drop_gravity(score.level) > 1);
+
+        assert_eq!(tgm.gravity(0), 64);
+        assert_eq!(tgm.gravity(200), 64);
+        assert_eq!(tgm.gravity(999), 1);
+    }
+}
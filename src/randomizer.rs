@@ -0,0 +1,112 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::tetromino::Tetromino;
+
+const TETROMINOS: [Tetromino; 7] = [
+    Tetromino::I,
+    Tetromino::J,
+    Tetromino::L,
+    Tetromino::O,
+    Tetromino::S,
+    Tetromino::T,
+    Tetromino::Z,
+];
+
+pub trait Randomizer {
+    fn take_next(&mut self) -> Tetromino;
+}
+
+pub struct Bag {
+    rng: StdRng,
+    inner: [Tetromino; 7],
+    idx: usize,
+}
+
+impl Bag {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self {
+            inner: Self::random_tetrominos(&mut rng),
+            rng,
+            idx: 0,
+        }
+    }
+
+    fn random_tetrominos(rng: &mut StdRng) -> [Tetromino; 7] {
+        use rand::seq::IndexedRandom;
+
+        TETROMINOS
+            .choose_multiple_array(rng)
+            .expect("both arrays should have a length of 7")
+    }
+}
+
+impl Randomizer for Bag {
+    fn take_next(&mut self) -> Tetromino {
+        if self.idx >= self.inner.len() {
+            self.idx = 0;
+            self.inner = Self::random_tetrominos(&mut self.rng);
+        }
+
+        let uninitialized_tetromino = Tetromino::I;
+        let current = std::mem::replace(&mut self.inner[self.idx], uninitialized_tetromino);
+        self.idx += 1;
+        current
+    }
+}
+
+pub struct Reroll {
+    rng: StdRng,
+    last: Option<Tetromino>,
+}
+
+impl Reroll {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            last: None,
+        }
+    }
+}
+
+impl Randomizer for Reroll {
+    fn take_next(&mut self) -> Tetromino {
+        use rand::Rng;
+        let rng = &mut self.rng;
+
+        let idx = rng.random_range(0..=TETROMINOS.len());
+        let next = match TETROMINOS.get(idx) {
+            Some(tetromino) if self.last.as_ref() != Some(tetromino) => tetromino.clone(),
+            _ => TETROMINOS[rng.random_range(0..TETROMINOS.len())].clone(),
+        };
+
+        self.last = Some(next.clone());
+        next
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Randomizer, Reroll, TETROMINOS};
+
+    #[test]
+    fn reroll_is_seeded_and_rarely_repeats() {
+        let draw = |seed| {
+            let mut reroll = Reroll::new(seed);
+            (0..7000).map(|_| reroll.take_next()).collect::<Vec<_>>()
+        };
+        let pieces = draw(7);
+        assert_eq!(pieces, draw(7));
+        assert_ne!(pieces, draw(8));
+
+        for tetromino in &TETROMINOS {
+            let count = pieces.iter().filter(|piece| *piece == tetromino).count();
+            assert!((700..1300).contains(&count), "{tetromino:?}: {count}");
+        }
+        // a repeat needs the first roll to hit the last piece or the blank slot
+        // and the reroll to land on it again: 2/8 * 1/7 instead of 1/7
+        let repeats = pieces.windows(2).filter(|pair| pair[0] == pair[1]).count();
+        assert!((140..360).contains(&repeats), "{repeats} repeats");
+    }
+}
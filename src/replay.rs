@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::actions::{Action, ActionsHeld};
+use crate::game::Game;
+use crate::mode::{Mode, Settings};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEvent {
+    pub tick: usize,
+    pub action: Action,
+    pub pressed: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub mode: Mode,
+    #[serde(with = "seed")]
+    pub seed: u64,
+    pub level: usize,
+    pub entry_delay: usize,
+    pub line_clear_delay: usize,
+    pub end_tick: usize,
+    pub events: Vec<ReplayEvent>,
+}
+
+impl Replay {
+    pub fn new(settings: &Settings, game: &Game) -> Self {
+        Self {
+            mode: settings.mode,
+            seed: game.seed,
+            level: game.score.level,
+            entry_delay: settings.entry_delay,
+            line_clear_delay: settings.line_clear_delay,
+            end_tick: 0,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn settings(&self) -> Settings {
+        let mut settings = Settings::new(self.mode);
+        settings.seed = Some(self.seed);
+        settings.level = Some(self.level);
+        settings.entry_delay = self.entry_delay;
+        settings.line_clear_delay = self.line_clear_delay;
+        settings
+    }
+
+    pub fn record(&mut self, tick: usize, action: Action, pressed: bool) {
+        self.events.push(ReplayEvent {
+            tick,
+            action,
+            pressed,
+        });
+    }
+
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), String> {
+        let replay = toml::to_string(self).map_err(|err| err.to_string())?;
+        fs::write(path.as_ref(), replay).map_err(|err| {
+            format!(
+                "could not write replay '{}': {err}",
+                path.as_ref().display()
+            )
+        })
+    }
+
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Replay, String> {
+        let replay = fs::read_to_string(path.as_ref())
+            .map_err(|err| format!("could not read replay '{}': {err}", path.as_ref().display()))?;
+        toml::from_str(&replay)
+            .map_err(|err| format!("invalid replay '{}': {err}", path.as_ref().display()))
+    }
+}
+
+mod seed {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    // toml integers are signed, so seeds above i64::MAX are stored as strings
+    pub fn serialize<S: Serializer>(seed: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&seed.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+pub struct Playback {
+    replay: Replay,
+    idx: usize,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, idx: 0 }
+    }
+
+    pub fn finished(&self, game: &Game) -> bool {
+        game.game_over || game.ticks >= self.replay.end_tick
+    }
+
+    pub fn apply(&mut self, game: &Game, actions: &mut ActionsHeld) {
+        while let Some(event) = self.replay.events.get(self.idx) {
+            if event.tick > game.ticks {
+                break;
+            }
+            if event.pressed {
+                actions.insert(event.action, event.tick);
+            } else {
+                actions.remove(&event.action);
+            }
+            self.idx += 1;
+        }
+    }
+
+    pub fn run(mut self) -> Game {
+        let mut game = Game::new(self.replay.settings());
+        let mut actions = ActionsHeld::new();
+        while !self.finished(&game) {
+            self.apply(&game, &mut actions);
+            game.step(&actions);
+        }
+        game
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Playback, Replay};
+    use crate::actions::{Action, ActionsHeld};
+    use crate::game::Game;
+    use crate::mode::{Mode, Settings};
+
+    #[test]
+    fn playback_reproduces_recorded_game() {
+        let settings = Settings::new(Mode::Guideline);
+        let mut game = Game::new(settings.clone());
+        let mut replay = Replay::new(&settings, &game);
+        let mut actions = ActionsHeld::new();
+
+        for _ in 0..600 {
+            if game.game_over {
+                break;
+            }
+            if game.ticks.is_multiple_of(40) {
+                let action = [Action::Left, Action::RotateCw, Action::HardDrop][game.ticks % 3];
+                actions.insert(action, game.ticks);
+                replay.record(game.ticks, action, true);
+            }
+            if game.ticks % 40 == 5 {
+                actions.reset();
+                for action in [Action::Left, Action::RotateCw, Action::HardDrop] {
+                    replay.record(game.ticks, action, false);
+                }
+            }
+            game.step(&actions);
+        }
+        replay.end_tick = game.ticks;
+
+        let replay: Replay = toml::from_str(&toml::to_string(&replay).unwrap()).unwrap();
+        let replayed = Playback::new(replay).run();
+        assert_eq!(replayed.board, game.board);
+        assert_eq!(replayed.score.points, game.score.points);
+    }
+}
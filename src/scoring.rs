@@ -0,0 +1,417 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Spin {
+    None,
+    Mini,
+    Full,
+}
+
+pub struct Lock {
+    pub lines_cleared: usize,
+    pub spin: Spin,
+    pub perfect_clear: bool,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Scored {
+    pub level_up: bool,
+    pub combo: Option<usize>,
+}
+
+impl Scored {
+    fn counted(level: usize, score: &Score, lines_cleared: usize) -> Self {
+        Self {
+            level_up: score.level > level,
+            combo: (lines_cleared > 0 && score.combo > 1).then(|| score.combo - 1),
+        }
+    }
+}
+
+pub struct Score {
+    pub level: usize,
+    pub points: usize,
+    pub lines: usize,
+    pub combo: usize,
+    pub back_to_back: bool,
+    pub pushdown: usize,
+    level_progress: usize,
+}
+
+impl Score {
+    pub const fn new(level: usize) -> Self {
+        Self {
+            level,
+            points: 0,
+            lines: 0,
+            combo: 0,
+            back_to_back: false,
+            pushdown: 0,
+            level_progress: 0,
+        }
+    }
+}
+
+pub trait ScoringRules {
+    fn initial_level(&self) -> usize {
+        0
+    }
+    fn soft_drop(&self, score: &mut Score, rows: usize) {
+        score.points += rows;
+    }
+    fn hard_drop(&self, score: &mut Score, rows: usize) {
+        score.points += rows * 2;
+    }
+    fn lock(&self, score: &mut Score, lock: &Lock) -> Scored;
+    fn grade(&self, _score: &Score) -> Option<&'static str> {
+        None
+    }
+}
+
+pub struct Reimtris;
+
+impl Reimtris {
+    fn point_multiplier_from_lines_cleared(lines_cleared: usize) -> f32 {
+        match lines_cleared {
+            0 => 0.0,
+            1 => 100.0,
+            2 => 300.0,
+            3 => 500.0,
+            4 => 800.0,
+            _ => unreachable!("we cannot clear more than 4 lines"),
+        }
+    }
+}
+
+impl ScoringRules for Reimtris {
+    fn lock(&self, score: &mut Score, lock: &Lock) -> Scored {
+        let lines_cleared = lock.lines_cleared;
+        let level = score.level;
+
+        score.lines += lines_cleared;
+        score.level_progress += lines_cleared;
+        if score.level_progress > score.level * 5 {
+            score.level += 1;
+            score.level_progress = 0;
+        }
+
+        let mut points =
+            score.level as f32 * Self::point_multiplier_from_lines_cleared(lines_cleared);
+
+        if score.back_to_back && lines_cleared == 4 {
+            points *= 1.5;
+        }
+        if lines_cleared > 0 {
+            points += (score.combo * 50 * score.level) as f32;
+        }
+
+        score.points += points as usize;
+
+        if lines_cleared == 4 {
+            score.back_to_back = true;
+        } else if lines_cleared > 0 {
+            score.back_to_back = false;
+        }
+
+        if lines_cleared > 0 {
+            score.combo += 1;
+        } else {
+            score.combo = 0;
+        }
+        Scored::counted(level, score, lines_cleared)
+    }
+}
+
+pub struct Guideline;
+
+impl Guideline {
+    fn action_points(lock: &Lock) -> usize {
+        match (lock.spin, lock.lines_cleared) {
+            (Spin::None, 0) => 0,
+            (Spin::None, 1) => 100,
+            (Spin::None, 2) => 300,
+            (Spin::None, 3) => 500,
+            (Spin::None, 4) => 800,
+            (Spin::Mini, 0) => 100,
+            (Spin::Mini, 1) => 200,
+            (Spin::Mini, _) => 400,
+            (Spin::Full, 0) => 400,
+            (Spin::Full, 1) => 800,
+            (Spin::Full, 2) => 1200,
+            (Spin::Full, _) => 1600,
+            _ => unreachable!("we cannot clear more than 4 lines"),
+        }
+    }
+
+    fn perfect_clear_points(lines_cleared: usize, back_to_back: bool) -> usize {
+        match lines_cleared {
+            1 => 800,
+            2 => 1200,
+            3 => 1800,
+            4 if back_to_back => 3200,
+            4 => 2000,
+            _ => 0,
+        }
+    }
+}
+
+impl ScoringRules for Guideline {
+    fn initial_level(&self) -> usize {
+        1
+    }
+
+    fn lock(&self, score: &mut Score, lock: &Lock) -> Scored {
+        let level = score.level;
+        let difficult = lock.lines_cleared == 4 || lock.spin != Spin::None;
+        let back_to_back = score.back_to_back && difficult && lock.lines_cleared > 0;
+
+        let mut points = Self::action_points(lock) * score.level;
+        if back_to_back {
+            points += points / 2;
+        }
+        if lock.lines_cleared > 0 {
+            points += 50 * score.combo * score.level;
+        }
+        if lock.perfect_clear {
+            points += Self::perfect_clear_points(lock.lines_cleared, back_to_back) * score.level;
+        }
+        score.points += points;
+
+        if lock.lines_cleared > 0 {
+            score.back_to_back = difficult;
+            score.combo += 1;
+        } else {
+            score.combo = 0;
+        }
+
+        score.lines += lock.lines_cleared;
+        score.level_progress += lock.lines_cleared;
+        if score.level_progress >= 10 {
+            score.level += 1;
+            score.level_progress -= 10;
+        }
+        Scored::counted(level, score, lock.lines_cleared)
+    }
+}
+
+pub struct Nes;
+
+impl ScoringRules for Nes {
+    fn soft_drop(&self, score: &mut Score, rows: usize) {
+        score.pushdown += rows;
+    }
+
+    fn hard_drop(&self, _score: &mut Score, _rows: usize) {}
+
+    fn lock(&self, score: &mut Score, lock: &Lock) -> Scored {
+        let level = score.level;
+        let base = match lock.lines_cleared {
+            0 => 0,
+            1 => 40,
+            2 => 100,
+            3 => 300,
+            4 => 1200,
+            _ => unreachable!("we cannot clear more than 4 lines"),
+        };
+        score.points += base * (score.level + 1) + score.pushdown;
+        score.pushdown = 0;
+
+        if lock.lines_cleared == 0 {
+            return Scored::default();
+        }
+
+        let first_level_up =
+            (score.level * 10 + 10).min((score.level * 10).saturating_sub(50).max(100));
+        score.lines += lock.lines_cleared;
+        score.level_progress += lock.lines_cleared;
+        let threshold = if score.lines == score.level_progress {
+            first_level_up
+        } else {
+            10
+        };
+        if score.level_progress >= threshold {
+            score.level += 1;
+            score.level_progress -= threshold;
+        }
+        Scored::counted(level, score, lock.lines_cleared)
+    }
+}
+
+pub struct Tgm;
+
+impl Tgm {
+    const GRADES: [(usize, &str); 18] = [
+        (120000, "S9"),
+        (100000, "S8"),
+        (82000, "S7"),
+        (66000, "S6"),
+        (52000, "S5"),
+        (40000, "S4"),
+        (30000, "S3"),
+        (22000, "S2"),
+        (16000, "S1"),
+        (12000, "1"),
+        (8000, "2"),
+        (5500, "3"),
+        (3500, "4"),
+        (2000, "5"),
+        (1400, "6"),
+        (800, "7"),
+        (400, "8"),
+        (0, "9"),
+    ];
+}
+
+impl ScoringRules for Tgm {
+    fn soft_drop(&self, score: &mut Score, rows: usize) {
+        score.pushdown += rows;
+    }
+
+    fn hard_drop(&self, _score: &mut Score, _rows: usize) {}
+
+    // the level rises on every piece and combo is a score multiplier,
+    // so only crossing into a new section of 100 levels is announced
+    fn lock(&self, score: &mut Score, lock: &Lock) -> Scored {
+        let lines_cleared = lock.lines_cleared;
+        let section = score.level / 100;
+        let pushdown = std::mem::take(&mut score.pushdown);
+
+        if lines_cleared == 0 {
+            score.combo = 1;
+            if score.level % 100 != 99 && score.level < 998 {
+                score.level += 1;
+            }
+            return Scored::default();
+        }
+
+        score.combo = score.combo.max(1) + 2 * lines_cleared - 2;
+        let bravo = if lock.perfect_clear { 4 } else { 1 };
+        score.points += ((score.level + lines_cleared).div_ceil(4) + pushdown)
+            * lines_cleared
+            * score.combo
+            * bravo;
+
+        score.lines += lines_cleared;
+        score.level = (score.level + lines_cleared).min(999);
+        Scored {
+            level_up: score.level / 100 > section,
+            combo: None,
+        }
+    }
+
+    fn grade(&self, score: &Score) -> Option<&'static str> {
+        Self::GRADES
+            .iter()
+            .find(|(threshold, _)| score.points >= *threshold)
+            .map(|(_, grade)| *grade)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Guideline, Lock, Nes, Reimtris, Score, Scored, ScoringRules, Spin, Tgm};
+
+    fn lock(lines_cleared: usize, spin: Spin) -> Lock {
+        Lock {
+            lines_cleared,
+            spin,
+            perfect_clear: false,
+        }
+    }
+
+    #[test]
+    fn reimtris_levels_up_before_scoring() {
+        let mut score = Score::new(Reimtris.initial_level());
+        Reimtris.lock(&mut score, &lock(1, Spin::None));
+        assert_eq!(score.level, 1);
+        assert_eq!(score.points, 100);
+
+        Reimtris.lock(&mut score, &lock(4, Spin::None));
+        assert_eq!(score.level, 1);
+        assert_eq!(score.points, 100 + 800 + 50);
+    }
+
+    #[test]
+    fn reimtris_lines_keep_counting_across_levels() {
+        let mut score = Score::new(Reimtris.initial_level());
+        for (cleared, level, lines) in [(1, 1, 1), (4, 1, 5), (2, 2, 7), (4, 2, 11)] {
+            Reimtris.lock(&mut score, &lock(cleared, Spin::None));
+            assert_eq!((score.level, score.lines), (level, lines));
+        }
+    }
+
+    #[test]
+    fn guideline_back_to_back_t_spin() {
+        let mut score = Score::new(Guideline.initial_level());
+        Guideline.lock(&mut score, &lock(4, Spin::None));
+        assert_eq!(score.points, 800);
+
+        Guideline.lock(&mut score, &lock(2, Spin::Full));
+        assert_eq!(score.points, 800 + 1800 + 50);
+        assert!(score.back_to_back);
+
+        Guideline.lock(&mut score, &lock(1, Spin::None));
+        assert!(!score.back_to_back);
+    }
+
+    #[test]
+    fn nes_level_up_depends_on_starting_level() {
+        let mut score = Score::new(5);
+        Nes.soft_drop(&mut score, 3);
+        Nes.hard_drop(&mut score, 10);
+        Nes.lock(&mut score, &lock(4, Spin::None));
+        assert_eq!(score.points, 1200 * 6 + 3);
+
+        for _ in 0..13 {
+            Nes.lock(&mut score, &lock(4, Spin::None));
+        }
+        assert_eq!(score.lines, 56);
+        assert_eq!(score.level, 5);
+
+        Nes.lock(&mut score, &lock(4, Spin::None));
+        assert_eq!(score.level, 6);
+    }
+
+    #[test]
+    fn tgm_grade_follows_points() {
+        let mut score = Score::new(Tgm.initial_level());
+        assert_eq!(Tgm.grade(&score), Some("9"));
+
+        score.points = 16000;
+        assert_eq!(Tgm.grade(&score), Some("S1"));
+    }
+
+    #[test]
+    fn rulesets_report_their_own_level_ups_and_combos() {
+        let mut score = Score::new(Reimtris.initial_level());
+        let scored = Reimtris.lock(&mut score, &lock(1, Spin::None));
+        assert_eq!(
+            scored,
+            Scored {
+                level_up: true,
+                combo: None
+            }
+        );
+        let scored = Reimtris.lock(&mut score, &lock(1, Spin::None));
+        assert_eq!(scored.combo, Some(1));
+
+        let mut score = Score::new(Tgm.initial_level());
+        for _ in 0..50 {
+            assert_eq!(
+                Tgm.lock(&mut score, &lock(0, Spin::None)),
+                Scored::default()
+            );
+        }
+        assert_eq!(
+            Tgm.lock(&mut score, &lock(2, Spin::None)),
+            Scored::default()
+        );
+        score.level = 98;
+        let scored = Tgm.lock(&mut score, &lock(2, Spin::None));
+        assert_eq!(
+            scored,
+            Scored {
+                level_up: true,
+                combo: None
+            }
+        );
+    }
+}
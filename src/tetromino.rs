@@ -9,7 +9,7 @@ pub enum Tetromino {
     Z,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Direction {
     Up,
     Right,
@@ -37,6 +37,46 @@ pub enum DirectionDiff {
     Ccw,
 }
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum RotationSystem {
+    Super,
+    Nintendo,
+}
+
+impl RotationSystem {
+    pub fn spawn_direction(&self) -> Direction {
+        match self {
+            RotationSystem::Super => Direction::Up,
+            RotationSystem::Nintendo => Direction::Down,
+        }
+    }
+
+    pub fn rotate(
+        &self,
+        tetromino: &Tetromino,
+        direction: &Direction,
+        diff: &DirectionDiff,
+    ) -> Direction {
+        match (self, tetromino) {
+            (RotationSystem::Nintendo, Tetromino::I | Tetromino::S | Tetromino::Z) => {
+                match direction {
+                    Direction::Down => Direction::Right,
+                    _ => Direction::Down,
+                }
+            }
+            (RotationSystem::Nintendo, Tetromino::O) => Direction::Down,
+            _ => direction.rotate(diff),
+        }
+    }
+
+    pub fn wall_kicks(&self) -> bool {
+        match self {
+            RotationSystem::Super => true,
+            RotationSystem::Nintendo => false,
+        }
+    }
+}
+
 impl Tetromino {
     pub fn pattern(&self, direction: &Direction) -> Vec<(usize, usize)> {
         self.raw_pattern(direction)
@@ -258,3 +298,48 @@ impl Tetromino {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{Direction, DirectionDiff, RotationSystem, Tetromino};
+
+    #[test]
+    fn nintendo_rotation_toggles_and_never_kicks() {
+        let nintendo = RotationSystem::Nintendo;
+        assert!(!nintendo.wall_kicks());
+        assert_eq!(nintendo.spawn_direction(), Direction::Down);
+
+        for tetromino in [Tetromino::I, Tetromino::S, Tetromino::Z] {
+            for diff in [DirectionDiff::Cw, DirectionDiff::Ccw] {
+                let rotated = nintendo.rotate(&tetromino, &Direction::Down, &diff);
+                assert_eq!(rotated, Direction::Right);
+                assert_eq!(
+                    nintendo.rotate(&tetromino, &rotated, &diff),
+                    Direction::Down
+                );
+            }
+        }
+        for diff in [DirectionDiff::Cw, DirectionDiff::Ccw] {
+            assert_eq!(
+                nintendo.rotate(&Tetromino::O, &Direction::Down, &diff),
+                Direction::Down
+            );
+        }
+
+        let mut direction = nintendo.spawn_direction();
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            direction = nintendo.rotate(&Tetromino::T, &direction, &DirectionDiff::Cw);
+            seen.push(direction);
+        }
+        assert_eq!(
+            seen,
+            [
+                Direction::Left,
+                Direction::Up,
+                Direction::Right,
+                Direction::Down
+            ]
+        );
+    }
+}